@@ -0,0 +1,38 @@
+//! Writes tests/people.orc, the fixture read by tests/test_orc.py: 3000 rows of
+//! (id, name, score) in three stripes of 1000 rows, with every seventh name null.
+//!
+//!     cargo run --example people_orc [-- <path>]
+
+use std::sync::Arc;
+use arrow::array::{ArrayRef, Float64Array, Int64Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use orc_rust::ArrowWriterBuilder;
+
+fn main() -> anyhow::Result<()> {
+    let path = std::env::args().nth(1).unwrap_or_else(|| "tests/people.orc".to_string());
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int64, true),
+        Field::new("name", DataType::Utf8, true),
+        Field::new("score", DataType::Float64, true),
+    ]));
+    let file = std::fs::File::create(&path)?;
+    let mut writer = ArrowWriterBuilder::new(file, schema.clone()).try_build()?;
+    // Several stripes, so column projection is exercised across stripe boundaries
+    for stripe in 0..3i64 {
+        let ids: Vec<i64> = (stripe * 1000..(stripe + 1) * 1000).collect();
+        let names: Vec<Option<String>> = ids.iter()
+            .map(|i| if i % 7 == 0 { None } else { Some(format!("row{}", i)) })
+            .collect();
+        let scores: Vec<f64> = ids.iter().map(|i| *i as f64 / 4.0).collect();
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(Int64Array::from(ids)),
+            Arc::new(StringArray::from(names)),
+            Arc::new(Float64Array::from(scores)),
+        ];
+        writer.write(&RecordBatch::try_new(schema.clone(), columns)?)?;
+        writer.flush_stripe()?;
+    }
+    writer.close()?;
+    Ok(())
+}
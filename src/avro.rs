@@ -0,0 +1,439 @@
+use std::io::{Read, Write};
+use std::sync::Arc;
+use anyhow::{Result, Context};
+use apache_avro::{Codec, Decimal, Reader, Schema as AvroSchema, Writer};
+use apache_avro::types::Value;
+use arrow::record_batch::RecordBatch;
+use arrow_array::{
+    Array, ArrayRef, BinaryArray, BooleanArray, Date32Array, Decimal128Array, FixedSizeBinaryArray,
+    Float32Array, Float64Array, Int32Array, Int64Array, ListArray, PrimitiveArray, StringArray,
+    StructArray, Time32MillisecondArray, Time64MicrosecondArray, TimestampMicrosecondArray,
+    TimestampMillisecondArray, TimestampNanosecondArray,
+};
+use arrow_array::cast::AsArray;
+use arrow_array::types::{
+    ArrowPrimitiveType, Date32Type, Decimal128Type, Float32Type, Float64Type, Int32Type, Int64Type,
+    Time32MillisecondType, Time64MicrosecondType, TimestampMicrosecondType, TimestampMillisecondType,
+    TimestampNanosecondType,
+};
+use arrow::buffer::{NullBuffer, OffsetBuffer};
+use arrow_cast::cast;
+use arrow_schema::{DataType, Field, Fields, Schema, TimeUnit};
+use serde_json::json;
+use crate::dataframe::DataFrame;
+
+/// Number of Avro records decoded into each record batch.
+pub const AVRO_BATCH_SIZE: usize = 65536;
+
+pub fn parse_codec(name: &str) -> Result<Codec> {
+    match name.to_ascii_lowercase().as_str() {
+        "null" | "none" => Ok(Codec::Null),
+        "deflate" => Ok(Codec::Deflate),
+        "snappy" => Ok(Codec::Snappy),
+        "zstd" | "zstandard" => Ok(Codec::Zstandard),
+        _ => Err(anyhow::anyhow!("Unknown Avro codec: {} (expected null, deflate, snappy or zstd)", name)),
+    }
+}
+
+/// Reads an Avro object container file. The writer schema must be a record;
+/// each of its fields becomes a column.
+pub fn read<R: Read>(input: R) -> Result<DataFrame> {
+    let reader = Reader::new(input)?;
+    let fields = match reader.writer_schema() {
+        AvroSchema::Record(record) => record.fields.iter()
+            .map(|f| arrow_field(&f.name, &f.schema))
+            .collect::<Result<Vec<_>>>()?,
+        _ => return Err(anyhow::anyhow!("Avro schema must be a record")),
+    };
+    let schema = Arc::new(Schema::new(fields));
+
+    let mut batches = Vec::new();
+    let mut rows: Vec<Value> = Vec::with_capacity(AVRO_BATCH_SIZE);
+    for value in reader {
+        rows.push(value?);
+        if rows.len() == AVRO_BATCH_SIZE {
+            batches.push(record_batch(&schema, &rows)?);
+            rows.clear();
+        }
+    }
+    if !rows.is_empty() || batches.is_empty() {
+        batches.push(record_batch(&schema, &rows)?);
+    }
+    Ok(DataFrame { batches })
+}
+
+fn record_batch(schema: &Arc<Schema>, rows: &[Value]) -> Result<RecordBatch> {
+    let mut columns = Vec::with_capacity(schema.fields().len());
+    for (i, field) in schema.fields().iter().enumerate() {
+        let values: Vec<&Value> = rows.iter()
+            .map(|row| match row {
+                Value::Record(fields) => Ok(&fields[i].1),
+                _ => Err(anyhow::anyhow!("Expected an Avro record")),
+            })
+            .collect::<Result<_>>()?;
+        columns.push(build_array(&values, field)?);
+    }
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
+}
+
+/// Maps an Avro field to an Arrow field. A union of null and one other type
+/// becomes a nullable field of that type.
+fn arrow_field(name: &str, schema: &AvroSchema) -> Result<Field> {
+    if let AvroSchema::Union(union) = schema {
+        let variants: Vec<&AvroSchema> = union.variants().iter()
+            .filter(|s| !matches!(s, AvroSchema::Null))
+            .collect();
+        if variants.len() != 1 || union.variants().len() != 2 {
+            return Err(anyhow::anyhow!("Unsupported Avro union for field {}: only unions of null and one type are supported", name));
+        }
+        return Ok(Field::new(name, arrow_type(name, variants[0])?, true));
+    }
+    Ok(Field::new(name, arrow_type(name, schema)?, matches!(schema, AvroSchema::Null)))
+}
+
+fn arrow_type(name: &str, schema: &AvroSchema) -> Result<DataType> {
+    Ok(match schema {
+        AvroSchema::Null => DataType::Null,
+        AvroSchema::Boolean => DataType::Boolean,
+        AvroSchema::Int => DataType::Int32,
+        AvroSchema::Long => DataType::Int64,
+        AvroSchema::Float => DataType::Float32,
+        AvroSchema::Double => DataType::Float64,
+        AvroSchema::Bytes => DataType::Binary,
+        AvroSchema::Fixed(fixed) => DataType::FixedSizeBinary(fixed.size as i32),
+        AvroSchema::String | AvroSchema::Enum(_) | AvroSchema::Uuid => DataType::Utf8,
+        AvroSchema::Date => DataType::Date32,
+        AvroSchema::TimeMillis => DataType::Time32(TimeUnit::Millisecond),
+        AvroSchema::TimeMicros => DataType::Time64(TimeUnit::Microsecond),
+        AvroSchema::TimestampMillis => DataType::Timestamp(TimeUnit::Millisecond, Some("+00:00".into())),
+        AvroSchema::TimestampMicros => DataType::Timestamp(TimeUnit::Microsecond, Some("+00:00".into())),
+        AvroSchema::TimestampNanos => DataType::Timestamp(TimeUnit::Nanosecond, Some("+00:00".into())),
+        AvroSchema::LocalTimestampMillis => DataType::Timestamp(TimeUnit::Millisecond, None),
+        AvroSchema::LocalTimestampMicros => DataType::Timestamp(TimeUnit::Microsecond, None),
+        AvroSchema::LocalTimestampNanos => DataType::Timestamp(TimeUnit::Nanosecond, None),
+        AvroSchema::Decimal(decimal) => DataType::Decimal128(decimal.precision as u8, decimal.scale as i8),
+        AvroSchema::Record(record) => DataType::Struct(
+            record.fields.iter()
+                .map(|f| arrow_field(&f.name, &f.schema))
+                .collect::<Result<Fields>>()?,
+        ),
+        AvroSchema::Array(array) => DataType::List(Arc::new(arrow_field("item", &array.items)?)),
+        other => return Err(anyhow::anyhow!("Unsupported Avro type for field {}: {:?}", name, other)),
+    })
+}
+
+/// Strips the union wrapper Avro puts around nullable values.
+fn unwrap_union(value: &Value) -> &Value {
+    match value {
+        Value::Union(_, inner) => inner,
+        other => other,
+    }
+}
+
+fn primitive<T: ArrowPrimitiveType>(values: &[&Value], f: impl Fn(&Value) -> Option<T::Native>) -> PrimitiveArray<T> {
+    values.iter().map(|v| f(unwrap_union(v))).collect()
+}
+
+fn decimal_to_i128(decimal: &Decimal) -> Option<i128> {
+    let bytes: Vec<u8> = decimal.try_into().ok()?;
+    if bytes.len() > 16 {
+        return None;
+    }
+    // Sign-extend the big-endian two's complement bytes to 16
+    let fill = if bytes.first().is_some_and(|b| b & 0x80 != 0) { 0xFF } else { 0 };
+    let mut buf = [fill; 16];
+    buf[16 - bytes.len()..].copy_from_slice(&bytes);
+    Some(i128::from_be_bytes(buf))
+}
+
+fn build_array(values: &[&Value], field: &Field) -> Result<ArrayRef> {
+    let array: ArrayRef = match field.data_type() {
+        DataType::Null => Arc::new(arrow_array::NullArray::new(values.len())),
+        DataType::Boolean => Arc::new(values.iter()
+            .map(|v| match unwrap_union(v) { Value::Boolean(b) => Some(*b), _ => None })
+            .collect::<BooleanArray>()),
+        DataType::Int32 => Arc::new(primitive::<Int32Type>(values, |v| match v { Value::Int(i) => Some(*i), _ => None })),
+        DataType::Int64 => Arc::new(primitive::<Int64Type>(values, |v| match v { Value::Long(l) => Some(*l), _ => None })),
+        DataType::Float32 => Arc::new(primitive::<Float32Type>(values, |v| match v { Value::Float(f) => Some(*f), _ => None })),
+        DataType::Float64 => Arc::new(primitive::<Float64Type>(values, |v| match v { Value::Double(d) => Some(*d), _ => None })),
+        DataType::Date32 => Arc::new(primitive::<Date32Type>(values, |v| match v { Value::Date(d) => Some(*d), _ => None })),
+        DataType::Time32(_) => Arc::new(primitive::<Time32MillisecondType>(values, |v| match v { Value::TimeMillis(t) => Some(*t), _ => None })),
+        DataType::Time64(_) => Arc::new(primitive::<Time64MicrosecondType>(values, |v| match v { Value::TimeMicros(t) => Some(*t), _ => None })),
+        DataType::Timestamp(unit, tz) => match unit {
+            TimeUnit::Millisecond => Arc::new(primitive::<TimestampMillisecondType>(values, |v| match v {
+                Value::TimestampMillis(t) | Value::LocalTimestampMillis(t) => Some(*t),
+                _ => None,
+            }).with_timezone_opt(tz.clone())),
+            TimeUnit::Microsecond => Arc::new(primitive::<TimestampMicrosecondType>(values, |v| match v {
+                Value::TimestampMicros(t) | Value::LocalTimestampMicros(t) => Some(*t),
+                _ => None,
+            }).with_timezone_opt(tz.clone())),
+            _ => Arc::new(primitive::<TimestampNanosecondType>(values, |v| match v {
+                Value::TimestampNanos(t) | Value::LocalTimestampNanos(t) => Some(*t),
+                _ => None,
+            }).with_timezone_opt(tz.clone())),
+        },
+        DataType::Decimal128(precision, scale) => Arc::new(
+            primitive::<Decimal128Type>(values, |v| match v { Value::Decimal(d) => decimal_to_i128(d), _ => None })
+                .with_precision_and_scale(*precision, *scale)?,
+        ),
+        DataType::Utf8 => Arc::new(values.iter()
+            .map(|v| match unwrap_union(v) {
+                Value::String(s) | Value::Enum(_, s) => Some(s.clone()),
+                Value::Uuid(u) => Some(u.to_string()),
+                _ => None,
+            })
+            .collect::<StringArray>()),
+        DataType::Binary => Arc::new(values.iter()
+            .map(|v| match unwrap_union(v) {
+                Value::Bytes(b) | Value::Fixed(_, b) => Some(b.as_slice()),
+                _ => None,
+            })
+            .collect::<BinaryArray>()),
+        DataType::FixedSizeBinary(size) => Arc::new(FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+            values.iter().map(|v| match unwrap_union(v) {
+                Value::Fixed(_, b) => Some(b.as_slice()),
+                _ => None,
+            }),
+            *size,
+        )?),
+        DataType::Struct(fields) => {
+            let nulls: NullBuffer = values.iter().map(|v| matches!(unwrap_union(v), Value::Record(_))).collect();
+            let mut children = Vec::with_capacity(fields.len());
+            for (i, child) in fields.iter().enumerate() {
+                let child_values: Vec<&Value> = values.iter()
+                    .map(|v| match unwrap_union(v) {
+                        Value::Record(items) => &items[i].1,
+                        _ => &Value::Null,
+                    })
+                    .collect();
+                children.push(build_array(&child_values, child)?);
+            }
+            Arc::new(StructArray::try_new(fields.clone(), children, Some(nulls))?)
+        }
+        DataType::List(item) => {
+            let nulls: NullBuffer = values.iter().map(|v| matches!(unwrap_union(v), Value::Array(_))).collect();
+            let mut lengths = Vec::with_capacity(values.len());
+            let mut items = Vec::new();
+            for v in values {
+                match unwrap_union(v) {
+                    Value::Array(elements) => {
+                        lengths.push(elements.len());
+                        items.extend(elements.iter());
+                    }
+                    _ => lengths.push(0),
+                }
+            }
+            let child = build_array(&items, item)?;
+            Arc::new(ListArray::try_new(item.clone(), OffsetBuffer::from_lengths(lengths), child, Some(nulls))?)
+        }
+        other => return Err(anyhow::anyhow!("Unsupported Arrow type for Avro field {}: {:?}", field.name(), other)),
+    };
+    Ok(array)
+}
+
+/// Writes the DataFrame as an Avro object container file with a record schema
+/// named `name`. Nullable columns become unions of null and the column type.
+pub fn write<W: Write>(df: &DataFrame, out: W, name: &str, codec: Codec) -> Result<()> {
+    let arrow_schema = match df.batches.first() {
+        Some(batch) => batch.schema(),
+        None => return Err(anyhow::anyhow!("DataFrame is empty")),
+    };
+    let fields: Vec<Field> = arrow_schema.fields().iter()
+        .map(|f| f.as_ref().clone().with_data_type(avro_compatible(f.data_type())))
+        .collect();
+    let schema_json = record_schema(&avro_name(name), &fields)?;
+    let schema = AvroSchema::parse(&schema_json).context("Failed to build Avro schema")?;
+
+    let mut writer = Writer::with_codec(&schema, out, codec);
+    for batch in &df.batches {
+        let mut columns = Vec::with_capacity(fields.len());
+        for (column, field) in batch.columns().iter().zip(&fields) {
+            let column = cast(column, field.data_type())?;
+            columns.push(avro_values(&column, field)?.into_iter());
+        }
+        for _ in 0..batch.num_rows() {
+            let record = fields.iter()
+                .zip(columns.iter_mut())
+                .map(|(field, values)| (field.name().clone(), values.next().unwrap_or(Value::Null)))
+                .collect();
+            writer.append(Value::Record(record))?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Avro names must start with a letter or underscore and contain only
+/// letters, digits and underscores.
+fn avro_name(name: &str) -> String {
+    let mut out: String = name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if !out.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        out.insert(0, '_');
+    }
+    out
+}
+
+/// Arrow types without a direct Avro counterpart are widened to one that has.
+fn avro_compatible(data_type: &DataType) -> DataType {
+    match data_type {
+        DataType::Int8 | DataType::Int16 | DataType::UInt8 | DataType::UInt16 => DataType::Int32,
+        DataType::UInt32 | DataType::UInt64 => DataType::Int64,
+        DataType::Float16 => DataType::Float32,
+        DataType::LargeUtf8 | DataType::Utf8View => DataType::Utf8,
+        DataType::LargeBinary | DataType::BinaryView => DataType::Binary,
+        DataType::Dictionary(_, value) => avro_compatible(value),
+        DataType::Date64 => DataType::Date32,
+        DataType::Time32(_) => DataType::Time32(TimeUnit::Millisecond),
+        DataType::Time64(_) => DataType::Time64(TimeUnit::Microsecond),
+        DataType::Timestamp(TimeUnit::Second, tz) => DataType::Timestamp(TimeUnit::Millisecond, tz.clone()),
+        DataType::Struct(fields) => DataType::Struct(
+            fields.iter()
+                .map(|f| f.as_ref().clone().with_data_type(avro_compatible(f.data_type())))
+                .collect(),
+        ),
+        DataType::List(item) | DataType::LargeList(item) => DataType::List(Arc::new(
+            item.as_ref().clone().with_data_type(avro_compatible(item.data_type())),
+        )),
+        other => other.clone(),
+    }
+}
+
+fn record_schema(name: &str, fields: &[Field]) -> Result<serde_json::Value> {
+    let fields = fields.iter()
+        .map(|f| Ok(json!({ "name": f.name(), "type": avro_field_type(f, name)? })))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(json!({ "type": "record", "name": name, "fields": fields }))
+}
+
+fn avro_field_type(field: &Field, parent: &str) -> Result<serde_json::Value> {
+    let avro = match field.data_type() {
+        DataType::Null => return Ok(json!("null")),
+        DataType::Boolean => json!("boolean"),
+        DataType::Int32 => json!("int"),
+        DataType::Int64 => json!("long"),
+        DataType::Float32 => json!("float"),
+        DataType::Float64 => json!("double"),
+        DataType::Utf8 => json!("string"),
+        DataType::Binary => json!("bytes"),
+        DataType::FixedSizeBinary(size) => json!({ "type": "fixed", "name": avro_name(&format!("{}_{}", parent, field.name())), "size": size }),
+        DataType::Date32 => json!({ "type": "int", "logicalType": "date" }),
+        DataType::Time32(_) => json!({ "type": "int", "logicalType": "time-millis" }),
+        DataType::Time64(_) => json!({ "type": "long", "logicalType": "time-micros" }),
+        DataType::Timestamp(unit, tz) => {
+            let unit = match unit {
+                TimeUnit::Microsecond => "micros",
+                TimeUnit::Nanosecond => "nanos",
+                _ => "millis",
+            };
+            // Timestamps with a time zone are instants; naive ones are local
+            let logical = if tz.is_some() { format!("timestamp-{}", unit) } else { format!("local-timestamp-{}", unit) };
+            json!({ "type": "long", "logicalType": logical })
+        }
+        DataType::Decimal128(precision, scale) => json!({ "type": "bytes", "logicalType": "decimal", "precision": precision, "scale": scale }),
+        DataType::Struct(fields) => {
+            let fields: Vec<Field> = fields.iter().map(|f| f.as_ref().clone()).collect();
+            record_schema(&avro_name(&format!("{}_{}", parent, field.name())), &fields)?
+        }
+        DataType::List(item) => json!({ "type": "array", "items": avro_field_type(item, &format!("{}_{}", parent, field.name()))? }),
+        other => return Err(anyhow::anyhow!("Unsupported type for Avro column {}: {:?}", field.name(), other)),
+    };
+    Ok(if field.is_nullable() { json!(["null", avro]) } else { avro })
+}
+
+/// Converts an array (already cast to its Avro-compatible type) into Avro values,
+/// wrapping them in the `["null", T]` union when the field is nullable. Null
+/// columns are written with the plain `"null"` schema and are never wrapped.
+fn avro_values(array: &ArrayRef, field: &Field) -> Result<Vec<Value>> {
+    let len = array.len();
+    let plain: Vec<Value> = match field.data_type() {
+        DataType::Null => vec![Value::Null; len],
+        DataType::Boolean => array.as_boolean().iter().map(|v| v.map_or(Value::Null, Value::Boolean)).collect(),
+        DataType::Int32 => primitive_values(array.as_any().downcast_ref::<Int32Array>(), Value::Int),
+        DataType::Int64 => primitive_values(array.as_any().downcast_ref::<Int64Array>(), Value::Long),
+        DataType::Float32 => primitive_values(array.as_any().downcast_ref::<Float32Array>(), Value::Float),
+        DataType::Float64 => primitive_values(array.as_any().downcast_ref::<Float64Array>(), Value::Double),
+        DataType::Date32 => primitive_values(array.as_any().downcast_ref::<Date32Array>(), Value::Date),
+        DataType::Time32(_) => primitive_values(array.as_any().downcast_ref::<Time32MillisecondArray>(), Value::TimeMillis),
+        DataType::Time64(_) => primitive_values(array.as_any().downcast_ref::<Time64MicrosecondArray>(), Value::TimeMicros),
+        DataType::Timestamp(TimeUnit::Millisecond, tz) => primitive_values(
+            array.as_any().downcast_ref::<TimestampMillisecondArray>(),
+            if tz.is_some() { Value::TimestampMillis } else { Value::LocalTimestampMillis },
+        ),
+        DataType::Timestamp(TimeUnit::Microsecond, tz) => primitive_values(
+            array.as_any().downcast_ref::<TimestampMicrosecondArray>(),
+            if tz.is_some() { Value::TimestampMicros } else { Value::LocalTimestampMicros },
+        ),
+        DataType::Timestamp(_, tz) => primitive_values(
+            array.as_any().downcast_ref::<TimestampNanosecondArray>(),
+            if tz.is_some() { Value::TimestampNanos } else { Value::LocalTimestampNanos },
+        ),
+        DataType::Decimal128(_, _) => primitive_values(
+            array.as_any().downcast_ref::<Decimal128Array>(),
+            |v| Value::Decimal(Decimal::from(minimal_be_bytes(v))),
+        ),
+        DataType::Utf8 => array.as_string::<i32>().iter().map(|v| v.map_or(Value::Null, |s| Value::String(s.to_string()))).collect(),
+        DataType::Binary => array.as_binary::<i32>().iter().map(|v| v.map_or(Value::Null, |b| Value::Bytes(b.to_vec()))).collect(),
+        DataType::FixedSizeBinary(size) => array.as_fixed_size_binary().iter()
+            .map(|v| v.map_or(Value::Null, |b| Value::Fixed(*size as usize, b.to_vec())))
+            .collect(),
+        DataType::Struct(fields) => {
+            let array = array.as_struct();
+            let mut children = fields.iter()
+                .zip(array.columns())
+                .map(|(f, c)| Ok(avro_values(c, f)?.into_iter()))
+                .collect::<Result<Vec<_>>>()?;
+            (0..len)
+                .map(|row| {
+                    let record: Vec<(String, Value)> = fields.iter()
+                        .zip(children.iter_mut())
+                        .map(|(f, values)| (f.name().clone(), values.next().unwrap_or(Value::Null)))
+                        .collect();
+                    if array.is_null(row) { Value::Null } else { Value::Record(record) }
+                })
+                .collect()
+        }
+        DataType::List(item) => {
+            let array = array.as_list::<i32>();
+            let offsets = array.value_offsets();
+            let mut items = avro_values(array.values(), item)?.into_iter().skip(offsets[0] as usize);
+            (0..len)
+                .map(|row| {
+                    let elements: Vec<Value> = items.by_ref().take((offsets[row + 1] - offsets[row]) as usize).collect();
+                    if array.is_null(row) { Value::Null } else { Value::Array(elements) }
+                })
+                .collect()
+        }
+        other => return Err(anyhow::anyhow!("Unsupported type for Avro column {}: {:?}", field.name(), other)),
+    };
+    if !field.is_nullable() || field.data_type() == &DataType::Null {
+        return Ok(plain);
+    }
+    Ok(plain.into_iter()
+        .map(|v| match v {
+            Value::Null => Value::Union(0, Box::new(Value::Null)),
+            v => Value::Union(1, Box::new(v)),
+        })
+        .collect())
+}
+
+fn primitive_values<T: ArrowPrimitiveType>(array: Option<&PrimitiveArray<T>>, f: impl Fn(T::Native) -> Value) -> Vec<Value> {
+    array.map_or_else(Vec::new, |a| a.iter().map(|v| v.map_or(Value::Null, &f)).collect())
+}
+
+/// Big-endian two's complement bytes of `value` without redundant sign bytes.
+fn minimal_be_bytes(value: i128) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let mut start = 0;
+    while start < 15 {
+        let (b, next) = (bytes[start], bytes[start + 1]);
+        if (b == 0 && next & 0x80 == 0) || (b == 0xFF && next & 0x80 != 0) {
+            start += 1;
+        } else {
+            break;
+        }
+    }
+    bytes[start..].to_vec()
+}
@@ -1,14 +1,21 @@
 use pyo3::prelude::*;
-use arrow::record_batch::RecordBatch;
+use pyo3::types::{PyCapsule, PyDict, PyList, PyTuple};
+use arrow::ffi::{from_ffi, FFI_ArrowArray, FFI_ArrowSchema};
+use arrow::ffi_stream::{ArrowArrayStreamReader, FFI_ArrowArrayStream};
+use arrow::record_batch::{RecordBatch, RecordBatchIterator, RecordBatchReader};
 use arrow_select::concat::concat_batches;
 use arrow_select::filter::filter_record_batch;
 use arrow_select::take::take;
 use arrow_ord::sort::sort_to_indices;
-use arrow_array::{StringArray, BooleanArray, UInt64Array, Float64Array, Array};
+use arrow_array::{make_array, StringArray, StructArray, BooleanArray, UInt64Array, Float64Array, Array};
 use arrow_cast::cast;
-use arrow_schema::{DataType, Field, Schema};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
 use std::collections::HashMap;
+use std::ffi::CString;
 use std::sync::Arc;
+use crate::expr::Node;
+use crate::series::Series;
+use crate::source::Sink;
 
 #[pyclass]
 #[derive(Clone)]
@@ -18,9 +25,16 @@ pub struct DataFrame {
 
 #[pymethods]
 impl DataFrame {
+    /// Builds a DataFrame from a `{column: values}` dict, or an empty one without `data`.
+    /// `schema` maps column names to dtype names for columns that shouldn't be inferred.
     #[new]
-    pub fn new() -> Self {
-        DataFrame { batches: Vec::new() }
+    #[pyo3(signature = (data=None, schema=None))]
+    pub fn new(data: Option<&Bound<'_, PyDict>>, schema: Option<HashMap<String, String>>) -> PyResult<Self> {
+        let dtypes = crate::pyvalues::parse_dtypes(schema)?;
+        match data {
+            Some(data) => crate::pyvalues::from_dict(data, &dtypes),
+            None => Ok(DataFrame { batches: Vec::new() }),
+        }
     }
 
     pub fn row_count(&self) -> usize {
@@ -40,6 +54,100 @@ impl DataFrame {
         (self.row_count(), self.column_count())
     }
 
+    #[getter]
+    pub fn columns(&self) -> Vec<String> {
+        self.schema().fields().iter().map(|f| f.name().clone()).collect()
+    }
+
+    /// Maps each column name to its dtype name.
+    #[getter]
+    pub fn dtypes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dtypes = PyDict::new_bound(py);
+        for field in self.schema().fields() {
+            dtypes.set_item(field.name(), crate::io::dtype_name(field.data_type()))?;
+        }
+        Ok(dtypes)
+    }
+
+    /// `df["col"]` returns the column as a Series; `df[["a", "b"]]` returns a
+    /// DataFrame with just those columns, in that order.
+    /// Unknown names raise KeyError either way.
+    pub fn __getitem__(&self, py: Python<'_>, key: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        let lookup = |name: &str| self.schema().index_of(name)
+            .map_err(|_| PyErr::new::<pyo3::exceptions::PyKeyError, _>(name.to_string()));
+        if let Ok(name) = key.extract::<String>() {
+            return Ok(self.column(lookup(&name)?).into_py(py));
+        }
+        if let Ok(names) = key.extract::<Vec<String>>() {
+            for name in &names {
+                lookup(name)?;
+            }
+            return Ok(self.select(names)?.into_py(py));
+        }
+        Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>("Expected a column name or a list of column names"))
+    }
+
+    /// Keeps the named columns, in the order given. The data is shared, not copied.
+    pub fn select(&self, columns: Vec<String>) -> PyResult<DataFrame> {
+        let indices = self.column_indices(&columns)?;
+        self.project(&indices)
+    }
+
+    /// Removes the named columns.
+    pub fn drop(&self, columns: Vec<String>) -> PyResult<DataFrame> {
+        let dropped = self.column_indices(&columns)?;
+        let kept: Vec<usize> = (0..self.column_count()).filter(|i| !dropped.contains(i)).collect();
+        self.project(&kept)
+    }
+
+    /// Moves the named columns to the front, in the order given; the other columns
+    /// follow in their current order.
+    pub fn reorder(&self, columns: Vec<String>) -> PyResult<DataFrame> {
+        let mut indices = self.column_indices(&columns)?;
+        let rest: Vec<usize> = (0..self.column_count()).filter(|i| !indices.contains(i)).collect();
+        indices.extend(rest);
+        self.project(&indices)
+    }
+
+    /// Renames columns with an `{"old": "new"}` mapping. Only the schema changes;
+    /// the column data and field metadata such as labels are kept.
+    pub fn rename(&self, mapping: HashMap<String, String>) -> PyResult<DataFrame> {
+        let schema = self.schema();
+        for old in mapping.keys() {
+            self.column_index(old)?;
+        }
+        let fields: Vec<Field> = schema.fields().iter()
+            .map(|f| match mapping.get(f.name()) {
+                Some(new) => f.as_ref().clone().with_name(new),
+                None => f.as_ref().clone(),
+            })
+            .collect();
+        let mut seen = std::collections::HashSet::new();
+        if let Some(f) = fields.iter().find(|f| !seen.insert(f.name().clone())) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Renaming would create a duplicate column: {}", f.name()
+            )));
+        }
+        let renamed = Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()));
+        let batches = self.batches.iter()
+            .map(|b| RecordBatch::try_new(renamed.clone(), b.columns().to_vec()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("{}", e)))?;
+        Ok(DataFrame { batches })
+    }
+
+    /// Maps each column name to its label from the source file's field metadata, or None.
+    #[getter]
+    pub fn column_labels<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let labels = PyDict::new_bound(py);
+        if let Some(batch) = self.batches.first() {
+            for field in batch.schema().fields() {
+                labels.set_item(field.name(), field.metadata().get("label"))?;
+            }
+        }
+        Ok(labels)
+    }
+
     #[allow(non_snake_case)]
     #[pyo3(signature = (n=None))]
     pub fn head(&self, n: Option<usize>) -> DataFrame {
@@ -59,108 +167,220 @@ impl DataFrame {
         DataFrame { batches: new_batches }
     }
 
-    pub fn to_csv(&self, path: &str) -> PyResult<()> {
+    pub fn to_csv(&self, path: Sink) -> PyResult<()> {
         crate::io::to_csv(self, path)
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
     }
 
-    pub fn to_parquet(&self, path: &str) -> PyResult<()> {
+    pub fn to_parquet(&self, path: Sink) -> PyResult<()> {
         crate::io::to_parquet(self, path)
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
     }
 
-    pub fn to_json(&self, path: &str) -> PyResult<()> {
+    pub fn to_json(&self, path: Sink) -> PyResult<()> {
         crate::io::to_json(self, path)
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
     }
 
-    pub fn to_excel(&self, path: &str) -> PyResult<()> {
+    pub fn to_excel(&self, path: Sink) -> PyResult<()> {
         crate::io::to_excel(self, path)
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
     }
 
-    pub fn filter_eq(&self, col_name: &str, value: &str) -> PyResult<DataFrame> {
-        let mut filtered_batches = Vec::new();
+    #[pyo3(signature = (path, name=None, version=5))]
+    pub fn to_xpt(&self, path: Sink, name: Option<&str>, version: u8) -> PyResult<()> {
+        crate::io::to_xpt(self, path, name, version)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
+    }
+
+    #[pyo3(signature = (path, codec="null"))]
+    pub fn to_avro(&self, path: Sink, codec: &str) -> PyResult<()> {
+        crate::io::to_avro(self, path, codec)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
+    }
 
+    #[pyo3(signature = (db_path, table, if_exists="fail"))]
+    pub fn to_sqlite(&self, db_path: Sink, table: &str, if_exists: &str) -> PyResult<()> {
+        crate::io::to_sqlite(self, db_path, table, if_exists)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
+    }
+
+    /// Exports the schema through the Arrow PyCapsule interface.
+    pub fn __arrow_c_schema__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyCapsule>> {
+        let schema = FFI_ArrowSchema::try_from(self.schema().as_ref())
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
+        PyCapsule::new_bound(py, schema, Some(CString::new("arrow_schema")?))
+    }
+
+    /// Exports the batches as an Arrow C stream through the Arrow PyCapsule interface.
+    /// The consumer shares the batches' buffers instead of copying them. Columns are
+    /// cast by position when a `requested_schema` capsule differs from the schema.
+    #[pyo3(signature = (requested_schema=None))]
+    pub fn __arrow_c_stream__<'py>(&self, py: Python<'py>, requested_schema: Option<Bound<'py, PyCapsule>>) -> PyResult<Bound<'py, PyCapsule>> {
+        let value_error = |e: String| PyErr::new::<pyo3::exceptions::PyValueError, _>(e);
+        let mut schema = self.schema();
+        let mut batches = self.batches.clone();
+
+        if let Some(capsule) = requested_schema {
+            // SAFETY: an "arrow_schema" capsule holds an FFI_ArrowSchema, which stays owned by the capsule
+            let requested = unsafe { &*(capsule.pointer() as *const FFI_ArrowSchema) };
+            let requested = Arc::new(Schema::try_from(requested).map_err(|e| value_error(e.to_string()))?);
+            if requested.fields().len() != schema.fields().len() {
+                return Err(value_error(format!(
+                    "Requested schema has {} columns, DataFrame has {}", requested.fields().len(), schema.fields().len()
+                )));
+            }
+            if requested != schema {
+                batches = batches.iter()
+                    .map(|batch| {
+                        let columns = batch.columns().iter().zip(requested.fields())
+                            .map(|(column, field)| cast(column, field.data_type()))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        RecordBatch::try_new(requested.clone(), columns)
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| value_error(e.to_string()))?;
+                schema = requested;
+            }
+        }
+
+        let reader = RecordBatchIterator::new(batches.into_iter().map(Ok), schema);
+        let stream = FFI_ArrowArrayStream::new(Box::new(reader));
+        PyCapsule::new_bound(py, stream, Some(CString::new("arrow_array_stream")?))
+    }
+
+    /// Converts to a `pyarrow.Table` without copying the data.
+    pub fn to_arrow<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
+        slf.py().import_bound("pyarrow")?.call_method1("table", (slf,))
+    }
+
+    /// Converts to a `pandas.DataFrame` by way of `to_arrow`.
+    pub fn to_pandas<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
+        Self::to_arrow(slf)?.call_method0("to_pandas")
+    }
+
+    /// Columns as a dict of lists (`orient="list"`) or rows as a list of dicts
+    /// (`orient="records"`).
+    #[pyo3(signature = (orient="list"))]
+    pub fn to_dict(&self, py: Python<'_>, orient: &str) -> PyResult<PyObject> {
+        let columns = crate::pyvalues::dataframe_to_columns(py, self)?;
+        match orient {
+            "list" => {
+                let dict = PyDict::new_bound(py);
+                for (name, values) in columns {
+                    dict.set_item(name, PyList::new_bound(py, values))?;
+                }
+                Ok(dict.into_py(py))
+            }
+            "records" => Ok(records(py, &columns, self.row_count())?.into_py(py)),
+            _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Unknown orient: {} (expected 'list' or 'records')", orient
+            ))),
+        }
+    }
+
+    /// Rows as a list of dicts.
+    pub fn to_pylist<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let columns = crate::pyvalues::dataframe_to_columns(py, self)?;
+        records(py, &columns, self.row_count())
+    }
+
+    /// Rows as a list of tuples, in column order.
+    pub fn rows<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let columns = crate::pyvalues::dataframe_to_columns(py, self)?;
+        let rows = (0..self.row_count())
+            .map(|r| PyTuple::new_bound(py, columns.iter().map(|(_, values)| &values[r])));
+        Ok(PyList::new_bound(py, rows))
+    }
+
+    /// Copies numeric and boolean columns into a 2-D NumPy array.
+    pub fn to_numpy<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        crate::pyvalues::dataframe_to_numpy(py, self)
+    }
+
+    /// Adds columns computed from expressions, or replaces columns of the same name.
+    /// Positional expressions are named after their alias or leftmost column;
+    /// keyword arguments name the column. All expressions see the original columns,
+    /// and two expressions may not produce the same name.
+    #[pyo3(signature = (*exprs, **named))]
+    pub fn with_columns(&self, exprs: Vec<Node>, named: Option<&Bound<'_, PyDict>>) -> PyResult<DataFrame> {
+        let mut outputs: Vec<(String, Node)> = exprs.into_iter().map(|e| (e.output_name(), e)).collect();
+        if let Some(named) = named {
+            for (name, node) in named.iter() {
+                outputs.push((name.extract()?, node.extract()?));
+            }
+        }
+        let mut seen = std::collections::HashSet::new();
+        if let Some((name, _)) = outputs.iter().find(|(name, _)| !seen.insert(name.as_str())) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Two expressions would create the same column: {}", name
+            )));
+        }
+
+        let batches = match self.batches.is_empty() {
+            true => vec![RecordBatch::new_empty(self.schema())],
+            false => self.batches.clone(),
+        };
+        let mut new_batches = Vec::with_capacity(batches.len());
+        for batch in &batches {
+            let mut fields: Vec<Field> = batch.schema().fields().iter().map(|f| f.as_ref().clone()).collect();
+            let mut columns = batch.columns().to_vec();
+            let computed = outputs.iter()
+                .map(|(_, node)| node.evaluate_array(batch))
+                .collect::<PyResult<Vec<_>>>()?;
+            for ((name, _), array) in outputs.iter().zip(computed) {
+                let field = Field::new(name, array.data_type().clone(), true);
+                match fields.iter().position(|f| f.name() == name) {
+                    Some(i) => {
+                        fields[i] = field;
+                        columns[i] = array;
+                    }
+                    None => {
+                        fields.push(field);
+                        columns.push(array);
+                    }
+                }
+            }
+            let new_batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("{}", e)))?;
+            new_batches.push(new_batch);
+        }
+        Ok(DataFrame { batches: new_batches })
+    }
+
+    /// Keeps the rows where a boolean expression is true; null counts as false.
+    pub fn filter(&self, predicate: Node) -> PyResult<DataFrame> {
+        let mut filtered_batches = Vec::new();
         for batch in &self.batches {
-            let col_idx = batch.schema().index_of(col_name)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            
-            let col = batch.column(col_idx);
-            let string_col = cast(col, &DataType::Utf8)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!("{}", e)))?;
-            let string_array = string_col.as_any().downcast_ref::<StringArray>().unwrap();
-            
-            let mask: BooleanArray = BooleanArray::from_unary(string_array, |s| s == value);
-            
+            let mask = predicate.evaluate_mask(batch)?;
             let filtered_batch = filter_record_batch(batch, &mask)
                 .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("{}", e)))?;
-            
             if filtered_batch.num_rows() > 0 {
                 filtered_batches.push(filtered_batch);
             }
         }
-
+        // Keep the schema when no row matches
+        if filtered_batches.is_empty() {
+            if let Some(batch) = self.batches.first() {
+                filtered_batches.push(batch.slice(0, 0));
+            }
+        }
         Ok(DataFrame { batches: filtered_batches })
     }
 
-    pub fn query(&self, expression: &str) -> PyResult<DataFrame> {
-        let parts: Vec<&str> = expression.split_whitespace().collect();
-        if parts.len() != 3 {
-            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
-                "Query expression must be in format 'column operator value'"
-            ));
-        }
-
-        let col_name = parts[0];
-        let op = parts[1];
-        let val_str = parts[2];
-
+    pub fn filter_eq(&self, col_name: &str, value: &str) -> PyResult<DataFrame> {
         let mut filtered_batches = Vec::new();
 
         for batch in &self.batches {
-            let col_idx = batch.schema().index_of(col_name)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
+            let col_idx = self.column_index(col_name)?;
             
             let col = batch.column(col_idx);
-            let mask = match col.data_type() {
-                DataType::Int64 => {
-                    let arr = col.as_any().downcast_ref::<arrow_array::Int64Array>().unwrap();
-                    let val = val_str.parse::<i64>().map_err(|_| PyErr::new::<pyo3::exceptions::PyValueError, _>("Invalid integer value"))?;
-                    match op {
-                        "<" => Ok(BooleanArray::from_unary(arr, |v| v < val)),
-                        ">" => Ok(BooleanArray::from_unary(arr, |v| v > val)),
-                        "<=" => Ok(BooleanArray::from_unary(arr, |v| v <= val)),
-                        ">=" => Ok(BooleanArray::from_unary(arr, |v| v >= val)),
-                        "==" => Ok(BooleanArray::from_unary(arr, |v| v == val)),
-                        "!=" => Ok(BooleanArray::from_unary(arr, |v| v != val)),
-                        _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Unsupported operator: {}", op))),
-                    }
-                },
-                DataType::Float64 => {
-                    let arr = col.as_any().downcast_ref::<arrow_array::Float64Array>().unwrap();
-                    let val = val_str.parse::<f64>().map_err(|_| PyErr::new::<pyo3::exceptions::PyValueError, _>("Invalid float value"))?;
-                    match op {
-                        "<" => Ok(BooleanArray::from_unary(arr, |v| v < val)),
-                        ">" => Ok(BooleanArray::from_unary(arr, |v| v > val)),
-                        "<=" => Ok(BooleanArray::from_unary(arr, |v| v <= val)),
-                        ">=" => Ok(BooleanArray::from_unary(arr, |v| v >= val)),
-                        "==" => Ok(BooleanArray::from_unary(arr, |v| v == val)),
-                        "!=" => Ok(BooleanArray::from_unary(arr, |v| v != val)),
-                        _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Unsupported operator: {}", op))),
-                    }
-                },
-                DataType::Utf8 => {
-                    let arr = col.as_any().downcast_ref::<arrow_array::StringArray>().unwrap();
-                    match op {
-                        "==" => Ok(BooleanArray::from_unary(arr, |v| v == val_str)),
-                        "!=" => Ok(BooleanArray::from_unary(arr, |v| v != val_str)),
-                        _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Unsupported operator for string: {}", op))),
-                    }
-                },
-                _ => Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!("Unsupported column type: {:?}", col.data_type()))),
-            }?;
-
+            let string_col = cast(col, &DataType::Utf8)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!("{}", e)))?;
+            let string_array = string_col.as_any().downcast_ref::<StringArray>().unwrap();
+            
+            let mask: BooleanArray = BooleanArray::from_unary(string_array, |s| s == value);
+            
             let filtered_batch = filter_record_batch(batch, &mask)
                 .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("{}", e)))?;
             
@@ -172,6 +392,13 @@ impl DataFrame {
         Ok(DataFrame { batches: filtered_batches })
     }
 
+    /// Filters rows with a predicate such as `price > 10 and region in ('EU', 'US')`.
+    /// See `query::parse` for the syntax.
+    pub fn query(&self, expression: &str) -> PyResult<DataFrame> {
+        let predicate = crate::query::parse(expression, &self.columns())?;
+        self.filter(predicate)
+    }
+
     #[pyo3(signature = (col_name, ascending=None))]
     pub fn sort(&self, col_name: &str, ascending: Option<bool>) -> PyResult<DataFrame> {
         if self.batches.is_empty() {
@@ -182,8 +409,7 @@ impl DataFrame {
         let combined_batch = concat_batches(&schema, &self.batches)
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("{}", e)))?;
 
-        let col_idx = schema.index_of(col_name)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
+        let col_idx = self.column_index(col_name)?;
         
         let col = combined_batch.column(col_idx);
         let options = arrow_ord::sort::SortOptions {
@@ -216,11 +442,10 @@ impl DataFrame {
     pub fn groupby_sum(&self, group_col: &str, agg_col: &str) -> PyResult<DataFrame> {
         if self.batches.is_empty() { return Ok(self.clone()); }
 
+        let g_idx = self.column_index(group_col)?;
+        let a_idx = self.column_index(agg_col)?;
         let mut groups: HashMap<String, f64> = HashMap::new();
         for batch in &self.batches {
-            let g_idx = batch.schema().index_of(group_col).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            let a_idx = batch.schema().index_of(agg_col).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            
             let g_col = cast(batch.column(g_idx), &DataType::Utf8).unwrap();
             let a_col = cast(batch.column(a_idx), &DataType::Float64).unwrap();
             
@@ -267,8 +492,8 @@ impl DataFrame {
         let schema_r = other.batches[0].schema();
         let batch_r = concat_batches(&schema_r, &other.batches).map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("{}", e)))?;
         
-        let idx_l = schema_l.index_of(on).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-        let idx_r = schema_r.index_of(on).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
+        let idx_l = self.column_index(on)?;
+        let idx_r = other.column_index(on)?;
         
         let arr_l = cast(batch_l.column(idx_l), &DataType::Utf8).unwrap();
         let arr_l_str = arr_l.as_any().downcast_ref::<StringArray>().unwrap();
@@ -377,7 +602,138 @@ impl DataFrame {
         let display_batch = RecordBatch::try_new(display_schema, display_columns)
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("{}", e)))?;
 
-        arrow::util::pretty::print_batches(&vec![display_batch])
+        arrow::util::pretty::print_batches(&[display_batch])
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("{}", e)))
     }
 }
+
+impl DataFrame {
+    /// Schema shared by all batches; a DataFrame without batches has no columns.
+    pub fn schema(&self) -> SchemaRef {
+        self.batches.first()
+            .map(|batch| batch.schema())
+            .unwrap_or_else(|| Arc::new(Schema::empty()))
+    }
+
+    /// Position of a column, raising ValueError for an unknown name.
+    pub fn column_index(&self, name: &str) -> PyResult<usize> {
+        self.schema().index_of(name)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))
+    }
+
+    /// The column at `index` across all batches. The arrays are shared, not copied.
+    pub fn column(&self, index: usize) -> Series {
+        let field = self.schema().field(index).clone();
+        Series {
+            name: field.name().clone(),
+            data_type: field.data_type().clone(),
+            chunks: self.batches.iter().map(|b| b.column(index).clone()).collect(),
+        }
+    }
+
+    /// Positions of the named columns, rejecting unknown and repeated names.
+    fn column_indices(&self, names: &[String]) -> PyResult<Vec<usize>> {
+        let mut indices = Vec::with_capacity(names.len());
+        for name in names {
+            let index = self.column_index(name)?;
+            if indices.contains(&index) {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Column listed twice: {}", name)));
+            }
+            indices.push(index);
+        }
+        Ok(indices)
+    }
+
+    /// A DataFrame with the columns at `indices`, sharing this one's arrays.
+    pub fn project(&self, indices: &[usize]) -> PyResult<DataFrame> {
+        let batches = self.batches.iter()
+            .map(|b| b.project(indices))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
+        Ok(DataFrame { batches })
+    }
+
+    /// Imports an object implementing the Arrow PyCapsule interface, taking over
+    /// its buffers instead of copying them. `__arrow_c_stream__` is preferred;
+    /// with `__arrow_c_array__`, a struct array becomes the columns and any other
+    /// array a single column.
+    pub fn from_arrow_object(obj: &Bound<'_, PyAny>) -> PyResult<DataFrame> {
+        let value_error = |e: String| PyErr::new::<pyo3::exceptions::PyValueError, _>(e);
+        if obj.hasattr("__arrow_c_stream__")? {
+            let capsule = obj.call_method0("__arrow_c_stream__")?;
+            let capsule = capsule.downcast::<PyCapsule>()?;
+            check_capsule_name(capsule, "arrow_array_stream")?;
+            // SAFETY: the capsule holds an FFI_ArrowArrayStream; from_raw moves it out and
+            // leaves a released stream behind for the capsule's destructor
+            let stream = unsafe { FFI_ArrowArrayStream::from_raw(capsule.pointer() as *mut FFI_ArrowArrayStream) };
+            let reader = ArrowArrayStreamReader::try_new(stream).map_err(|e| value_error(e.to_string()))?;
+            let schema = reader.schema();
+            let mut batches = reader
+                .collect::<Result<Vec<RecordBatch>, _>>()
+                .map_err(|e| value_error(e.to_string()))?;
+            if batches.is_empty() {
+                batches.push(RecordBatch::new_empty(schema));
+            }
+            return Ok(DataFrame { batches });
+        }
+
+        if obj.hasattr("__arrow_c_array__")? {
+            let (schema_capsule, array_capsule): (Bound<'_, PyCapsule>, Bound<'_, PyCapsule>) =
+                obj.call_method0("__arrow_c_array__")?.extract()?;
+            check_capsule_name(&schema_capsule, "arrow_schema")?;
+            check_capsule_name(&array_capsule, "arrow_array")?;
+            // SAFETY: as above, the array is moved out of its capsule; the schema is only borrowed
+            let data = unsafe {
+                let schema = &*(schema_capsule.pointer() as *const FFI_ArrowSchema);
+                let array = FFI_ArrowArray::from_raw(array_capsule.pointer() as *mut FFI_ArrowArray);
+                from_ffi(array, schema)
+            }.map_err(|e| value_error(e.to_string()))?;
+
+            let batch = if let DataType::Struct(_) = data.data_type() {
+                let array = StructArray::from(data);
+                if array.null_count() > 0 {
+                    return Err(value_error("Cannot import a struct array with null rows as a DataFrame".to_string()));
+                }
+                RecordBatch::from(array)
+            } else {
+                // SAFETY: the schema capsule is still alive and owned by Python
+                let schema = unsafe { &*(schema_capsule.pointer() as *const FFI_ArrowSchema) };
+                let name = match schema.name() {
+                    Some(name) if !name.is_empty() => name.to_string(),
+                    _ => "column_1".to_string(),
+                };
+                let array = make_array(data);
+                let field = Field::new(name, array.data_type().clone(), true);
+                RecordBatch::try_new(Arc::new(Schema::new(vec![field])), vec![array])
+                    .map_err(|e| value_error(e.to_string()))?
+            };
+            return Ok(DataFrame { batches: vec![batch] });
+        }
+
+        Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+            "Expected an object implementing __arrow_c_stream__ or __arrow_c_array__",
+        ))
+    }
+}
+
+fn records<'py>(py: Python<'py>, columns: &[(String, Vec<PyObject>)], rows: usize) -> PyResult<Bound<'py, PyList>> {
+    let list = PyList::empty_bound(py);
+    for r in 0..rows {
+        let dict = PyDict::new_bound(py);
+        for (name, values) in columns {
+            dict.set_item(name, &values[r])?;
+        }
+        list.append(dict)?;
+    }
+    Ok(list)
+}
+
+fn check_capsule_name(capsule: &Bound<'_, PyCapsule>, expected: &str) -> PyResult<()> {
+    let name = capsule.name()?.map(|n| n.to_string_lossy().into_owned());
+    if name.as_deref() != Some(expected) {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Expected a PyCapsule named {}, got {}", expected, name.unwrap_or_else(|| "an unnamed capsule".to_string())
+        )));
+    }
+    Ok(())
+}
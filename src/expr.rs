@@ -0,0 +1,624 @@
+use std::fmt;
+use std::sync::Arc;
+use arrow::record_batch::RecordBatch;
+use arrow_arith::boolean::{and_kleene, is_not_null, is_null, not, or_kleene};
+use arrow_arith::numeric::{add, div, mul, neg, rem, sub};
+use arrow_array::cast::AsArray;
+use arrow_array::{new_null_array, Array, ArrayRef, BooleanArray, Datum, NullArray, StringArray, UInt32Array};
+use arrow_cast::{cast, cast_with_options, CastOptions};
+use arrow_ord::cmp::{eq, gt, gt_eq, lt, lt_eq, neq};
+use arrow_schema::{ArrowError, DataType};
+use arrow_select::filter::prep_null_mask_filter;
+use arrow_select::take::take;
+use arrow_select::zip::zip;
+use arrow_string::like::{contains, ends_with, ilike, like, starts_with};
+use arrow_string::regexp::{regexp_is_match, regexp_is_match_scalar};
+use pyo3::basic::CompareOp;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    And,
+    Or,
+    Like,
+    ILike,
+    StartsWith,
+    EndsWith,
+    Contains,
+    /// Regular expression match
+    Matches,
+    /// Case-insensitive regular expression match
+    IMatches,
+}
+
+impl BinaryOp {
+    fn symbol(self) -> &'static str {
+        match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Rem => "%",
+            BinaryOp::Eq => "==",
+            BinaryOp::NotEq => "!=",
+            BinaryOp::Lt => "<",
+            BinaryOp::LtEq => "<=",
+            BinaryOp::Gt => ">",
+            BinaryOp::GtEq => ">=",
+            BinaryOp::And => "&",
+            BinaryOp::Or => "|",
+            BinaryOp::Like => "like",
+            BinaryOp::ILike => "ilike",
+            BinaryOp::StartsWith => "starts_with",
+            BinaryOp::EndsWith => "ends_with",
+            BinaryOp::Contains => "contains",
+            BinaryOp::Matches => "matches",
+            BinaryOp::IMatches => "imatches",
+        }
+    }
+
+    /// Operators on strings, shown as method calls.
+    fn is_string_match(self) -> bool {
+        matches!(self, BinaryOp::Like | BinaryOp::ILike | BinaryOp::StartsWith | BinaryOp::EndsWith
+            | BinaryOp::Contains | BinaryOp::Matches | BinaryOp::IMatches)
+    }
+
+    fn is_comparison(self) -> bool {
+        matches!(self, BinaryOp::Eq | BinaryOp::NotEq | BinaryOp::Lt | BinaryOp::LtEq | BinaryOp::Gt | BinaryOp::GtEq)
+    }
+}
+
+/// An expression tree, evaluated one record batch at a time.
+#[derive(Clone, Debug)]
+pub enum Node {
+    Column(String),
+    /// A one-element array, with the Python repr of the value for display
+    Literal(ArrayRef, String),
+    Binary(BinaryOp, Box<Node>, Box<Node>),
+    Not(Box<Node>),
+    Negate(Box<Node>),
+    IsNull(Box<Node>),
+    IsNotNull(Box<Node>),
+    Cast { node: Box<Node>, dtype: DataType, strict: bool },
+    Alias(Box<Node>, String),
+    When { branches: Vec<(Node, Node)>, otherwise: Option<Box<Node>> },
+}
+
+/// The result of evaluating a node: a column, or a literal that applies to every row.
+#[derive(Clone)]
+pub struct Value {
+    pub array: ArrayRef,
+    pub is_scalar: bool,
+}
+
+impl Datum for Value {
+    fn get(&self) -> (&dyn Array, bool) {
+        (self.array.as_ref(), self.is_scalar)
+    }
+}
+
+impl Value {
+    fn column(array: ArrayRef) -> Value {
+        Value { array, is_scalar: false }
+    }
+
+    /// Applies a kernel to the underlying array, keeping scalars scalar.
+    fn map(&self, f: impl FnOnce(&dyn Array) -> Result<ArrayRef, ArrowError>) -> PyResult<Value> {
+        Ok(Value { array: f(self.array.as_ref()).map_err(arrow_error)?, is_scalar: self.is_scalar })
+    }
+
+    /// The value as a column of `len` rows.
+    pub fn into_array(self, len: usize) -> PyResult<ArrayRef> {
+        if !self.is_scalar {
+            return Ok(self.array);
+        }
+        if self.array.is_null(0) {
+            return Ok(new_null_array(self.array.data_type(), len));
+        }
+        take(&self.array, &UInt32Array::from(vec![0; len]), None).map_err(arrow_error)
+    }
+
+    fn cast(&self, dtype: &DataType, strict: bool) -> PyResult<Value> {
+        if self.array.data_type() == dtype {
+            return Ok(self.clone());
+        }
+        let options = CastOptions { safe: !strict, ..Default::default() };
+        self.map(|a| cast_with_options(a, dtype, &options))
+    }
+
+    fn unpack_dictionary(self) -> PyResult<Value> {
+        match self.array.data_type() {
+            DataType::Dictionary(_, values) => self.cast(&values.as_ref().clone(), true),
+            _ => Ok(self),
+        }
+    }
+
+    fn booleans(&self, len: usize, what: &str) -> PyResult<BooleanArray> {
+        let value = match self.array.data_type() {
+            DataType::Boolean => self.clone(),
+            DataType::Null => self.cast(&DataType::Boolean, false)?,
+            other => return Err(PyTypeError::new_err(format!("{} must be boolean, got {}", what, other))),
+        };
+        Ok(value.into_array(len)?.as_boolean().clone())
+    }
+}
+
+impl Node {
+    pub fn binary(op: BinaryOp, left: Node, right: Node) -> Node {
+        Node::Binary(op, Box::new(left), Box::new(right))
+    }
+
+    /// `node == v1 | node == v2 | ...`; false for an empty list.
+    pub fn is_in(node: Node, values: Vec<Node>) -> Node {
+        values.into_iter()
+            .map(|value| Node::binary(BinaryOp::Eq, node.clone(), value))
+            .reduce(|acc, eq| Node::binary(BinaryOp::Or, acc, eq))
+            .unwrap_or_else(|| Node::Literal(Arc::new(BooleanArray::from(vec![false])), "False".to_string()))
+    }
+
+    /// `lower <= node & node <= upper`, bounds included.
+    pub fn between(node: Node, lower: Node, upper: Node) -> Node {
+        Node::binary(
+            BinaryOp::And,
+            Node::binary(BinaryOp::GtEq, node.clone(), lower),
+            Node::binary(BinaryOp::LtEq, node, upper),
+        )
+    }
+
+    /// The name a derived column gets unless it is aliased: the leftmost column the
+    /// expression refers to, or "literal".
+    pub fn output_name(&self) -> String {
+        match self {
+            Node::Column(name) | Node::Alias(_, name) => name.clone(),
+            Node::Literal(_, _) => "literal".to_string(),
+            Node::Binary(_, left, _) => left.output_name(),
+            Node::Not(node) | Node::Negate(node) | Node::IsNull(node) | Node::IsNotNull(node) => node.output_name(),
+            Node::Cast { node, .. } => node.output_name(),
+            Node::When { branches, .. } => branches[0].1.output_name(),
+        }
+    }
+
+    /// Evaluates the expression as a column of the batch.
+    pub fn evaluate_array(&self, batch: &RecordBatch) -> PyResult<ArrayRef> {
+        self.evaluate(batch)?.into_array(batch.num_rows())
+    }
+
+    /// Evaluates a predicate as a filter mask, where nulls count as false.
+    pub fn evaluate_mask(&self, batch: &RecordBatch) -> PyResult<BooleanArray> {
+        let mask = self.evaluate(batch)?.booleans(batch.num_rows(), "A filter")?;
+        Ok(if mask.null_count() > 0 { prep_null_mask_filter(&mask) } else { mask })
+    }
+
+    pub fn evaluate(&self, batch: &RecordBatch) -> PyResult<Value> {
+        let rows = batch.num_rows();
+        match self {
+            Node::Column(name) => {
+                let array = batch.column_by_name(name)
+                    .ok_or_else(|| PyValueError::new_err(format!("Unknown column: {}", name)))?;
+                Ok(Value::column(array.clone()))
+            }
+            Node::Literal(array, _) => Ok(Value { array: array.clone(), is_scalar: true }),
+            Node::Binary(op, left, right) => {
+                binary(*op, left.evaluate(batch)?, right.evaluate(batch)?, rows)
+            }
+            Node::Not(node) => {
+                let value = node.evaluate(batch)?;
+                let is_scalar = value.is_scalar;
+                let len = if is_scalar { 1 } else { rows };
+                let negated = not(&value.booleans(len, "The operand of ~")?).map_err(arrow_error)?;
+                Ok(Value { array: Arc::new(negated), is_scalar })
+            }
+            Node::Negate(node) => node.evaluate(batch)?.map(neg),
+            Node::IsNull(node) => node.evaluate(batch)?.map(|a| Ok(Arc::new(is_null(a)?) as ArrayRef)),
+            Node::IsNotNull(node) => node.evaluate(batch)?.map(|a| Ok(Arc::new(is_not_null(a)?) as ArrayRef)),
+            Node::Cast { node, dtype, strict } => node.evaluate(batch)?.cast(dtype, *strict),
+            Node::Alias(node, _) => node.evaluate(batch),
+            Node::When { branches, otherwise } => {
+                let mut values = branches.iter()
+                    .map(|(_, then)| then.evaluate(batch))
+                    .collect::<PyResult<Vec<_>>>()?;
+                values.push(match otherwise {
+                    Some(node) => node.evaluate(batch)?,
+                    None => Value { array: Arc::new(NullArray::new(1)), is_scalar: true },
+                });
+                let dtype = values.iter().try_fold(DataType::Null, |dtype, value| {
+                    common_type(&dtype, value.array.data_type()).ok_or_else(|| PyTypeError::new_err(format!(
+                        "when/then branches have incompatible types {} and {}", dtype, value.array.data_type()
+                    )))
+                })?;
+                let values = values.iter().map(|v| v.cast(&dtype, true)).collect::<PyResult<Vec<_>>>()?;
+
+                // Later branches are applied first so that the first matching condition wins
+                let (otherwise, thens) = values.split_last().unwrap();
+                let mut result = otherwise.clone();
+                for ((condition, _), then) in branches.iter().zip(thens).rev() {
+                    let mask = condition.evaluate_mask(batch)?;
+                    result = Value::column(zip(&mask, then, &result).map_err(arrow_error)?);
+                }
+                Ok(result)
+            }
+        }
+    }
+}
+
+fn binary(op: BinaryOp, left: Value, right: Value, rows: usize) -> PyResult<Value> {
+    let is_scalar = left.is_scalar && right.is_scalar;
+    let len = if is_scalar { 1 } else { rows };
+    if matches!(op, BinaryOp::And | BinaryOp::Or) {
+        let (l, r) = (left.booleans(len, "The operands of & and |")?, right.booleans(len, "The operands of & and |")?);
+        let result = if op == BinaryOp::And { and_kleene(&l, &r) } else { or_kleene(&l, &r) };
+        return Ok(Value { array: Arc::new(result.map_err(arrow_error)?), is_scalar });
+    }
+
+    if op.is_string_match() {
+        return Ok(Value { array: Arc::new(string_match(op, left, right, len)?), is_scalar });
+    }
+
+    let (left, right) = coerce(op, left, right)?;
+    let array: ArrayRef = match op {
+        BinaryOp::Add => add(&left, &right).map_err(arrow_error)?,
+        BinaryOp::Sub => sub(&left, &right).map_err(arrow_error)?,
+        BinaryOp::Mul => mul(&left, &right).map_err(arrow_error)?,
+        BinaryOp::Div => div(&left, &right).map_err(arrow_error)?,
+        BinaryOp::Rem => rem(&left, &right).map_err(arrow_error)?,
+        BinaryOp::Eq => Arc::new(eq(&left, &right).map_err(arrow_error)?),
+        BinaryOp::NotEq => Arc::new(neq(&left, &right).map_err(arrow_error)?),
+        BinaryOp::Lt => Arc::new(lt(&left, &right).map_err(arrow_error)?),
+        BinaryOp::LtEq => Arc::new(lt_eq(&left, &right).map_err(arrow_error)?),
+        BinaryOp::Gt => Arc::new(gt(&left, &right).map_err(arrow_error)?),
+        BinaryOp::GtEq => Arc::new(gt_eq(&left, &right).map_err(arrow_error)?),
+        _ => unreachable!(),
+    };
+    Ok(Value { array, is_scalar })
+}
+
+/// `like`, `ilike`, prefix, suffix and substring tests and regular expression
+/// matches, with the arrow string kernels. Both operands must be strings.
+fn string_match(op: BinaryOp, left: Value, right: Value, len: usize) -> PyResult<BooleanArray> {
+    let (mut left, mut right) = (left.unpack_dictionary()?, right.unpack_dictionary()?);
+    for value in [&mut left, &mut right] {
+        match value.array.data_type() {
+            DataType::Null => *value = value.cast(&DataType::Utf8, true)?,
+            dtype if !is_string(dtype) => return Err(PyTypeError::new_err(format!(
+                "{} needs string operands, got {}", op.symbol(), dtype
+            ))),
+            _ => {}
+        }
+    }
+    if left.array.data_type() != right.array.data_type() {
+        left = left.cast(&DataType::Utf8, true)?;
+        right = right.cast(&DataType::Utf8, true)?;
+    }
+
+    let result = match op {
+        BinaryOp::Like => like(&left, &right),
+        BinaryOp::ILike => ilike(&left, &right),
+        BinaryOp::StartsWith => starts_with(&left, &right),
+        BinaryOp::EndsWith => ends_with(&left, &right),
+        BinaryOp::Contains => contains(&left, &right),
+        _ => {
+            let flag = (op == BinaryOp::IMatches).then_some("i");
+            let strings = left.into_array(len)?;
+            if right.is_scalar {
+                if right.array.is_null(0) {
+                    return Ok(BooleanArray::new_null(len));
+                }
+                let pattern = cast(&right.array, &DataType::Utf8).map_err(arrow_error)?;
+                let pattern = pattern.as_string::<i32>().value(0);
+                match strings.data_type() {
+                    DataType::LargeUtf8 => regexp_is_match_scalar(strings.as_string::<i64>(), pattern, flag),
+                    DataType::Utf8View => regexp_is_match_scalar(strings.as_string_view(), pattern, flag),
+                    _ => regexp_is_match_scalar(strings.as_string::<i32>(), pattern, flag),
+                }
+            } else {
+                let strings = cast(&strings, &DataType::Utf8).map_err(arrow_error)?;
+                let patterns = cast(&right.array, &DataType::Utf8).map_err(arrow_error)?;
+                let flags = flag.map(|flag| StringArray::from(vec![flag; len]));
+                regexp_is_match(strings.as_string::<i32>(), patterns.as_string::<i32>(), flags.as_ref())
+            }
+        }
+    };
+    result.map_err(arrow_error)
+}
+
+/// Casts the operands of an arithmetic or comparison operator to a shared type.
+///
+/// Dictionary columns are compared by their values. A literal compared with a
+/// column takes the column's type when it fits, so `uint8_col == 5` and
+/// `decimal_col > 10` stay in the column's type; otherwise, and always for
+/// arithmetic so `uint8_col + 250` cannot overflow, numbers meet at Int64, UInt64
+/// or Float64, and `/` always divides as Float64. A string compared with another type is
+/// parsed as that type, so `col("day") > "2024-01-01"` compares dates, and dates
+/// compared with timestamps become timestamps. Other combinations, such as
+/// timestamp minus timestamp, go to the kernels as they are.
+fn coerce(op: BinaryOp, left: Value, right: Value) -> PyResult<(Value, Value)> {
+    let (left, right) = (left.unpack_dictionary()?, right.unpack_dictionary()?);
+    let (l, r) = (left.array.data_type().clone(), right.array.data_type().clone());
+    if op == BinaryOp::Div && l.is_numeric() && r.is_numeric() {
+        return Ok((left.cast(&DataType::Float64, true)?, right.cast(&DataType::Float64, true)?));
+    }
+    if l == r {
+        return Ok((left, right));
+    }
+    // When a column is compared with a literal, the column's type wins
+    let (column, literal) = if left.is_scalar { (&right, &left) } else { (&left, &right) };
+    let literal = Some(literal).filter(|_| op.is_comparison() && !column.is_scalar && literal.is_scalar);
+    let column = column.array.data_type();
+    let target = match (&l, &r) {
+        (DataType::Null, _) => r.clone(),
+        (_, DataType::Null) => l.clone(),
+        _ if l.is_numeric() && r.is_numeric() => match literal {
+            Some(literal) if fits(literal, column) => column.clone(),
+            _ => numeric_type(&l, &r),
+        },
+        _ if op.is_comparison() && is_string(&l) && !is_string(&r) => r.clone(),
+        _ if op.is_comparison() && is_string(&r) && !is_string(&l) => l.clone(),
+        (DataType::Utf8, DataType::LargeUtf8) | (DataType::LargeUtf8, DataType::Utf8) => DataType::LargeUtf8,
+        (DataType::Timestamp(_, _), DataType::Timestamp(_, _)) if op.is_comparison() => column.clone(),
+        (DataType::Timestamp(_, _), DataType::Date32 | DataType::Date64) if op.is_comparison() => l.clone(),
+        (DataType::Date32 | DataType::Date64, DataType::Timestamp(_, _)) if op.is_comparison() => r.clone(),
+        (DataType::Date32, DataType::Date64) | (DataType::Date64, DataType::Date32) if op.is_comparison() => column.clone(),
+        _ if is_string(&l) && is_string(&r) => DataType::Utf8,
+        _ => return Ok((left, right)),
+    };
+    let cast_operand = |value: &Value| {
+        value.cast(&target, true).map_err(|_| PyValueError::new_err(format!(
+            "Cannot compare or combine {} with {} for {}", l, r, op.symbol()
+        )))
+    };
+    Ok((cast_operand(&left)?, cast_operand(&right)?))
+}
+
+/// Whether a numeric literal converts to the column's type without loss: integers
+/// into any numeric type they fit, floats only into floating-point types.
+fn fits(literal: &Value, column: &DataType) -> bool {
+    let dtype = literal.array.data_type();
+    (dtype.is_integer() || dtype.is_floating() && column.is_floating()) && literal.cast(column, true).is_ok()
+}
+
+fn is_string(dtype: &DataType) -> bool {
+    matches!(dtype, DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View)
+}
+
+fn numeric_type(l: &DataType, r: &DataType) -> DataType {
+    if !l.is_integer() || !r.is_integer() {
+        DataType::Float64
+    } else if l.is_unsigned_integer() && r.is_unsigned_integer() {
+        DataType::UInt64
+    } else {
+        DataType::Int64
+    }
+}
+
+/// The type the branches of a `when` expression are cast to.
+fn common_type(a: &DataType, b: &DataType) -> Option<DataType> {
+    match (a, b) {
+        _ if a == b => Some(a.clone()),
+        (DataType::Null, other) | (other, DataType::Null) => Some(other.clone()),
+        _ if a.is_numeric() && b.is_numeric() => Some(numeric_type(a, b)),
+        _ => None,
+    }
+}
+
+fn arrow_error(e: ArrowError) -> PyErr {
+    PyValueError::new_err(format!("{}", e))
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Node::Column(name) => write!(f, "col({:?})", name),
+            Node::Literal(_, repr) => write!(f, "{}", repr),
+            Node::Binary(BinaryOp::IMatches, left, right) => write!(f, "{}.matches({}, case_insensitive=True)", left, right),
+            Node::Binary(op, left, right) if op.is_string_match() => write!(f, "{}.{}({})", left, op.symbol(), right),
+            Node::Binary(op, left, right) => write!(f, "({} {} {})", left, op.symbol(), right),
+            Node::Not(node) => write!(f, "~{}", node),
+            Node::Negate(node) => write!(f, "-{}", node),
+            Node::IsNull(node) => write!(f, "{}.is_null()", node),
+            Node::IsNotNull(node) => write!(f, "{}.is_not_null()", node),
+            Node::Cast { node, dtype, .. } => write!(f, "{}.cast({:?})", node, crate::io::dtype_name(dtype)),
+            Node::Alias(node, name) => write!(f, "{}.alias({:?})", node, name),
+            Node::When { branches, otherwise } => {
+                for (i, (condition, then)) in branches.iter().enumerate() {
+                    let prefix = if i == 0 { "" } else { "." };
+                    write!(f, "{}when({}).then({})", prefix, condition, then)?;
+                }
+                match otherwise {
+                    Some(node) => write!(f, ".otherwise({})", node),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// Expressions, `Then` results and plain Python values, which become literals.
+impl<'py> FromPyObject<'py> for Node {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(expr) = ob.downcast::<Expr>() {
+            return Ok(expr.borrow().node.clone());
+        }
+        if let Ok(then) = ob.downcast::<Then>() {
+            return Ok(then.borrow().node(None));
+        }
+        let array = crate::pyvalues::scalar_from_value(ob)?;
+        Ok(Node::Literal(array, ob.repr()?.to_string()))
+    }
+}
+
+/// A column expression, built with `col`, `lit` and `when` and evaluated by
+/// `DataFrame.with_columns` and `DataFrame.filter`.
+#[pyclass]
+#[derive(Clone)]
+pub struct Expr {
+    pub node: Node,
+}
+
+impl Expr {
+    fn binary(&self, op: BinaryOp, other: Node) -> Expr {
+        Expr { node: Node::binary(op, self.node.clone(), other) }
+    }
+
+    fn reflected(&self, op: BinaryOp, other: Node) -> Expr {
+        Expr { node: Node::binary(op, other, self.node.clone()) }
+    }
+}
+
+#[pymethods]
+impl Expr {
+    pub fn __repr__(&self) -> String {
+        self.node.to_string()
+    }
+
+    pub fn __bool__(&self) -> PyResult<bool> {
+        Err(PyTypeError::new_err("An expression has no truth value; use &, | and ~ instead of and, or and not"))
+    }
+
+    pub fn __add__(&self, other: Node) -> Expr { self.binary(BinaryOp::Add, other) }
+    pub fn __radd__(&self, other: Node) -> Expr { self.reflected(BinaryOp::Add, other) }
+    pub fn __sub__(&self, other: Node) -> Expr { self.binary(BinaryOp::Sub, other) }
+    pub fn __rsub__(&self, other: Node) -> Expr { self.reflected(BinaryOp::Sub, other) }
+    pub fn __mul__(&self, other: Node) -> Expr { self.binary(BinaryOp::Mul, other) }
+    pub fn __rmul__(&self, other: Node) -> Expr { self.reflected(BinaryOp::Mul, other) }
+    pub fn __truediv__(&self, other: Node) -> Expr { self.binary(BinaryOp::Div, other) }
+    pub fn __rtruediv__(&self, other: Node) -> Expr { self.reflected(BinaryOp::Div, other) }
+    pub fn __mod__(&self, other: Node) -> Expr { self.binary(BinaryOp::Rem, other) }
+    pub fn __rmod__(&self, other: Node) -> Expr { self.reflected(BinaryOp::Rem, other) }
+    pub fn __and__(&self, other: Node) -> Expr { self.binary(BinaryOp::And, other) }
+    pub fn __rand__(&self, other: Node) -> Expr { self.reflected(BinaryOp::And, other) }
+    pub fn __or__(&self, other: Node) -> Expr { self.binary(BinaryOp::Or, other) }
+    pub fn __ror__(&self, other: Node) -> Expr { self.reflected(BinaryOp::Or, other) }
+
+    pub fn __neg__(&self) -> Expr {
+        Expr { node: Node::Negate(Box::new(self.node.clone())) }
+    }
+
+    pub fn __invert__(&self) -> Expr {
+        Expr { node: Node::Not(Box::new(self.node.clone())) }
+    }
+
+    pub fn __richcmp__(&self, other: Node, op: CompareOp) -> Expr {
+        let op = match op {
+            CompareOp::Eq => BinaryOp::Eq,
+            CompareOp::Ne => BinaryOp::NotEq,
+            CompareOp::Lt => BinaryOp::Lt,
+            CompareOp::Le => BinaryOp::LtEq,
+            CompareOp::Gt => BinaryOp::Gt,
+            CompareOp::Ge => BinaryOp::GtEq,
+        };
+        self.binary(op, other)
+    }
+
+    pub fn is_null(&self) -> Expr {
+        Expr { node: Node::IsNull(Box::new(self.node.clone())) }
+    }
+
+    pub fn is_not_null(&self) -> Expr {
+        Expr { node: Node::IsNotNull(Box::new(self.node.clone())) }
+    }
+
+    /// Converts to the named dtype. With `strict=False`, values that can't be
+    /// converted become null instead of raising an error.
+    #[pyo3(signature = (dtype, strict=true))]
+    pub fn cast(&self, dtype: &str, strict: bool) -> PyResult<Expr> {
+        let dtype = crate::io::parse_dtype(dtype).map_err(|e| PyValueError::new_err(format!("{}", e)))?;
+        Ok(Expr { node: Node::Cast { node: Box::new(self.node.clone()), dtype, strict } })
+    }
+
+    /// SQL `LIKE`: `%` matches any run of characters and `_` any single character.
+    pub fn like(&self, pattern: Node) -> Expr { self.binary(BinaryOp::Like, pattern) }
+
+    /// Case-insensitive `like`.
+    pub fn ilike(&self, pattern: Node) -> Expr { self.binary(BinaryOp::ILike, pattern) }
+
+    pub fn starts_with(&self, prefix: Node) -> Expr { self.binary(BinaryOp::StartsWith, prefix) }
+    pub fn ends_with(&self, suffix: Node) -> Expr { self.binary(BinaryOp::EndsWith, suffix) }
+    pub fn contains(&self, substring: Node) -> Expr { self.binary(BinaryOp::Contains, substring) }
+
+    /// True where a regular expression matches anywhere in the string; anchor it
+    /// with `^` and `$` to match the whole string.
+    #[pyo3(signature = (pattern, case_insensitive=false))]
+    pub fn matches(&self, pattern: Node, case_insensitive: bool) -> Expr {
+        self.binary(if case_insensitive { BinaryOp::IMatches } else { BinaryOp::Matches }, pattern)
+    }
+
+    /// True where the value equals one of `values`.
+    pub fn is_in(&self, values: Vec<Node>) -> Expr {
+        Expr { node: Node::is_in(self.node.clone(), values) }
+    }
+
+    /// True where `lower <= value <= upper`.
+    pub fn is_between(&self, lower: Node, upper: Node) -> Expr {
+        Expr { node: Node::between(self.node.clone(), lower, upper) }
+    }
+
+    pub fn alias(&self, name: String) -> Expr {
+        Expr { node: Node::Alias(Box::new(self.node.clone()), name) }
+    }
+}
+
+/// `when(condition)`, waiting for its `then` value.
+#[pyclass]
+pub struct When {
+    branches: Vec<(Node, Node)>,
+    condition: Node,
+}
+
+impl When {
+    pub fn new(condition: Node) -> When {
+        When { branches: Vec::new(), condition }
+    }
+}
+
+#[pymethods]
+impl When {
+    pub fn then(&self, value: Node) -> Then {
+        let mut branches = self.branches.clone();
+        branches.push((self.condition.clone(), value));
+        Then { branches }
+    }
+}
+
+/// `when(...).then(...)`: chain another `when` or finish with `otherwise`. Used as
+/// an expression directly, rows matching no condition are null.
+#[pyclass]
+pub struct Then {
+    branches: Vec<(Node, Node)>,
+}
+
+impl Then {
+    fn node(&self, otherwise: Option<Node>) -> Node {
+        Node::When { branches: self.branches.clone(), otherwise: otherwise.map(Box::new) }
+    }
+}
+
+#[pymethods]
+impl Then {
+    pub fn __repr__(&self) -> String {
+        self.node(None).to_string()
+    }
+
+    pub fn when(&self, condition: Node) -> When {
+        When { branches: self.branches.clone(), condition }
+    }
+
+    pub fn otherwise(&self, value: Node) -> Expr {
+        Expr { node: self.node(Some(value)) }
+    }
+
+    pub fn alias(&self, name: String) -> Expr {
+        Expr { node: Node::Alias(Box::new(self.node(None)), name) }
+    }
+}
@@ -1,32 +1,76 @@
 use std::fs::File;
-use std::io::{BufReader, Seek, SeekFrom};
-use arrow_csv::{ReaderBuilder, Writer, reader::infer_schema_from_files};
-use arrow::record_batch::RecordBatch;
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use arrow_csv::{ReaderBuilder, Writer, reader::Format as CsvFormat};
+use arrow::record_batch::{RecordBatch, RecordBatchReader};
 use anyhow::{Result, Context};
 use crate::dataframe::DataFrame;
+use crate::xport::{self, XportVersion};
+use crate::stata;
+use crate::spss;
+use crate::avro;
+use crate::sqlite::{self, IfExists};
+use crate::source::{Sink, Source, SourceReader};
+use rusqlite::{Connection, OpenFlags, MAIN_DB};
+use std::collections::HashMap;
 use std::sync::Arc;
 use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
 use parquet::arrow::ArrowWriter;
+use orc_rust::ArrowReaderBuilder as OrcReaderBuilder;
+use orc_rust::projection::ProjectionMask;
 use arrow_json::ReaderBuilder as JsonReaderBuilder;
 use arrow_json::LineDelimitedWriter;
 use arrow_json::reader::infer_json_schema;
-use calamine::{Reader, Xlsx, open_workbook};
-use rust_xlsxwriter::Workbook;
-use arrow_array::{StringArray, Array};
-use arrow_schema::{Field, Schema, DataType};
+use calamine::{Reader, Xlsx};
+use rust_xlsxwriter::{ExcelDateTime, Format, Table, TableColumn, TableStyle, Workbook, Worksheet};
+use arrow_array::cast::AsArray;
+use arrow_array::{Array, BooleanArray, Float64Array, StringArray, TimestampMillisecondArray};
+use arrow_schema::{Field, Schema, DataType, TimeUnit};
 use sas7bdat::{SasReader, CellValue};
-use arrow_array::{Float64Array, TimestampMillisecondArray};
+use sas7bdat::dataset::Variable as SasVariable;
+use sas7bdat::parser::{ColumnKind, NumericKind, OwnedRowIterator, RowIteratorCore, StreamingRow};
+use arrow_array::ArrayRef;
+use arrow_array::types::{Date32Type, TimestampMicrosecondType};
+use arrow_cast::{cast_with_options, CastOptions};
+use arrow_cast::display::{ArrayFormatter, FormatOptions};
+use arrow_cast::parse::Parser;
+use arrow_array::builder::{Date32Builder, Float64Builder, StringBuilder, Time64MicrosecondBuilder, TimestampMillisecondBuilder};
 
 use rayon::prelude::*;
-use memmap2::Mmap;
-pub fn read_csv(path: &str) -> Result<DataFrame> {
-    let file = File::open(path).with_context(|| format!("Failed to open CSV file: {}", path))?;
-    let mmap = unsafe { Mmap::map(&file)? };
-    let bytes = &mmap[..];
+use encoding_rs::{Encoding, UTF_8};
+use encoding_rs_io::{DecodeReaderBytes, DecodeReaderBytesBuilder};
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Looks up a text encoding by label, e.g. `"utf-8"`, `"latin1"`, `"cp1252"` or `"utf-16le"`.
+pub fn parse_encoding(label: &str) -> Result<&'static Encoding> {
+    Encoding::for_label(label.trim().as_bytes())
+        .ok_or_else(|| anyhow::anyhow!("Unknown encoding: {}", label))
+}
+
+/// Opens text input as a UTF-8 stream, transcoding from `encoding` as it is
+/// read and dropping a leading byte order mark.
+fn open_text(source: &Source, encoding: &'static Encoding) -> Result<BufReader<DecodeReaderBytes<SourceReader, Vec<u8>>>> {
+    let reader = source.open().with_context(|| format!("Failed to open file: {}", source.name()))?;
+    let decoder = DecodeReaderBytesBuilder::new()
+        .encoding(Some(encoding))
+        .strip_bom(true)
+        .build(reader);
+    Ok(BufReader::new(decoder))
+}
+
+pub fn read_csv(source: &Source, encoding: Option<&'static Encoding>) -> Result<DataFrame> {
+    if let Some(encoding) = encoding.filter(|e| *e != UTF_8) {
+        return read_csv_transcoded(source, encoding);
+    }
+
+    let data = source.map().with_context(|| format!("Failed to open CSV file: {}", source.name()))?;
+    let bytes = data.strip_prefix(UTF8_BOM).unwrap_or(&data);
 
     // Faster schema inference by limiting to 1000 records
-    let schema = infer_schema_from_files(&[path.to_string()], b',', Some(1000), true)
-        .with_context(|| format!("Failed to infer schema for CSV file: {}", path))?;
+    let (schema, _) = CsvFormat::default()
+        .with_header(true)
+        .infer_schema(bytes, Some(1000))
+        .with_context(|| format!("Failed to infer schema for CSV file: {}", source.name()))?;
     let schema_arc = Arc::new(schema);
 
     // Determine parallel segments
@@ -45,7 +89,71 @@ pub fn read_csv(path: &str) -> Result<DataFrame> {
         return Ok(DataFrame { batches });
     }
 
-    let chunk_size = bytes.len() / n_threads;
+    let offsets = line_chunk_offsets(bytes, n_threads, Some(b'"'));
+
+    // Process chunks in parallel
+    let results: Result<Vec<Vec<RecordBatch>>> = offsets
+        .windows(2)
+        .enumerate()
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .map(|(id, window)| {
+            let start = window[0];
+            let end = window[1];
+            if start >= end {
+                return Ok(vec![]);
+            }
+
+            let chunk_bytes = &bytes[start..end];
+            let mut builder = ReaderBuilder::new(schema_arc.clone())
+                .with_batch_size(65536);
+            
+            // Only the first chunk should treat the first line as a header
+            if id == 0 {
+                builder = builder.with_header(true);
+            } else {
+                builder = builder.with_header(false);
+            }
+
+            let csv_reader = builder.build(chunk_bytes)?;
+            let chunk_batches: Vec<RecordBatch> = csv_reader
+                .collect::<std::result::Result<Vec<RecordBatch>, _>>()?;
+            
+            Ok(chunk_batches)
+        })
+        .collect();
+
+    let batches = results?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    Ok(DataFrame { batches })
+}
+
+/// Reads a CSV file in another encoding. The file is decoded twice as a stream,
+/// once to infer the schema and once to parse it, instead of being transcoded
+/// into memory up front.
+fn read_csv_transcoded(source: &Source, encoding: &'static Encoding) -> Result<DataFrame> {
+    let (schema, _) = CsvFormat::default()
+        .with_header(true)
+        .infer_schema(open_text(source, encoding)?, Some(1000))
+        .with_context(|| format!("Failed to infer schema for CSV file: {}", source.name()))?;
+    let csv_reader = ReaderBuilder::new(Arc::new(schema))
+        .with_header(true)
+        .with_batch_size(65536)
+        .build(open_text(source, encoding)?)?;
+    let batches = csv_reader
+        .collect::<std::result::Result<Vec<RecordBatch>, _>>()
+        .context("Failed to read CSV batches")?;
+    Ok(DataFrame { batches })
+}
+
+/// Splits `bytes` into roughly `n_chunks` ranges that each end just after a
+/// newline, returning the boundaries (starting with 0 and ending with the length).
+/// With a `quote` byte, newlines inside quoted fields are not treated as boundaries.
+fn line_chunk_offsets(bytes: &[u8], n_chunks: usize, quote: Option<u8>) -> Vec<usize> {
+    let chunk_size = bytes.len() / n_chunks;
     let mut offsets = Vec::new();
     offsets.push(0);
 
@@ -53,15 +161,15 @@ pub fn read_csv(path: &str) -> Result<DataFrame> {
     let mut last_pos = 0;
     
     // Scan for boundaries
-    for i in 1..n_threads {
+    for i in 1..n_chunks {
         let target = i * chunk_size;
         let mut pos = target;
         
         // We need to know the state (in_quotes) at the start of the chunk.
         // The most robust way is to scan from the last known boundary.
         let mut current_in_quotes = in_quotes;
-        for j in last_pos..pos {
-            if bytes[j] == b'"' {
+        for &b in &bytes[last_pos..pos] {
+            if Some(b) == quote {
                 current_in_quotes = !current_in_quotes;
             }
         }
@@ -69,7 +177,7 @@ pub fn read_csv(path: &str) -> Result<DataFrame> {
         
         // Now find the next newline that is NOT inside quotes
         while pos < bytes.len() {
-            if bytes[pos] == b'"' {
+            if Some(bytes[pos]) == quote {
                 in_quotes = !in_quotes;
             } else if bytes[pos] == b'\n' && !in_quotes {
                 pos += 1;
@@ -86,58 +194,250 @@ pub fn read_csv(path: &str) -> Result<DataFrame> {
     offsets.push(bytes.len());
     offsets.dedup();
 
-    // Process chunks in parallel
+    offsets
+}
+
+/// Field layout and types for `read_fwf`.
+#[derive(Clone, Default)]
+pub struct FwfOptions {
+    /// Half-open `(start, end)` character ranges of each field
+    pub colspecs: Vec<(usize, usize)>,
+    pub names: Option<Vec<String>>,
+    pub dtypes: HashMap<String, DataType>,
+    /// Text encoding of the file; UTF-8 when unset
+    pub encoding: Option<&'static Encoding>,
+}
+
+/// Maps a dtype name such as `"int64"`, `"float64"`, `"str"` or `"date"` to an Arrow type.
+pub fn parse_dtype(name: &str) -> Result<DataType> {
+    Ok(match name.to_ascii_lowercase().as_str() {
+        "int8" => DataType::Int8,
+        "int16" => DataType::Int16,
+        "int32" => DataType::Int32,
+        "int64" | "int" => DataType::Int64,
+        "uint8" => DataType::UInt8,
+        "uint16" => DataType::UInt16,
+        "uint32" => DataType::UInt32,
+        "uint64" => DataType::UInt64,
+        "float32" => DataType::Float32,
+        "float64" | "float" => DataType::Float64,
+        "bool" | "boolean" => DataType::Boolean,
+        "str" | "string" | "utf8" => DataType::Utf8,
+        "date" | "date32" => DataType::Date32,
+        "datetime" | "timestamp" => DataType::Timestamp(TimeUnit::Microsecond, None),
+        _ => return Err(anyhow::anyhow!("Unknown dtype: {}", name)),
+    })
+}
+
+/// The name `parse_dtype` accepts for a type, or Arrow's own name for types it
+/// doesn't cover.
+pub fn dtype_name(dtype: &DataType) -> String {
+    match dtype {
+        DataType::Int8 => "int8".into(),
+        DataType::Int16 => "int16".into(),
+        DataType::Int32 => "int32".into(),
+        DataType::Int64 => "int64".into(),
+        DataType::UInt8 => "uint8".into(),
+        DataType::UInt16 => "uint16".into(),
+        DataType::UInt32 => "uint32".into(),
+        DataType::UInt64 => "uint64".into(),
+        DataType::Float32 => "float32".into(),
+        DataType::Float64 => "float64".into(),
+        DataType::Boolean => "bool".into(),
+        DataType::Utf8 => "str".into(),
+        DataType::Date32 => "date".into(),
+        DataType::Timestamp(TimeUnit::Microsecond, None) => "datetime".into(),
+        other => other.to_string(),
+    }
+}
+
+/// Reads a fixed-width text file, splitting it into line-aligned chunks that are
+/// parsed in parallel like `read_csv`. Fields are trimmed and empty fields are null.
+/// Columns without an explicit dtype are inferred from the first 1000 lines.
+pub fn read_fwf(source: &Source, options: &FwfOptions) -> Result<DataFrame> {
+    let colspecs = &options.colspecs;
+    let names = match &options.names {
+        Some(names) if names.len() != colspecs.len() => {
+            return Err(anyhow::anyhow!("Got {} names for {} fields", names.len(), colspecs.len()));
+        }
+        Some(names) => names.clone(),
+        None => (1..=colspecs.len()).map(|i| format!("column_{}", i)).collect(),
+    };
+    if let Some(name) = options.dtypes.keys().find(|k| !names.contains(k)) {
+        return Err(anyhow::anyhow!("dtype given for unknown column: {}", name));
+    }
+    if let Some(encoding) = options.encoding.filter(|e| *e != UTF_8) {
+        return read_fwf_transcoded(source, encoding, &names, options);
+    }
+
+    let data = source.map().with_context(|| format!("Failed to open fixed-width file: {}", source.name()))?;
+    let bytes = &data[..];
+
+    let sample: Vec<_> = fwf_lines(bytes).take(1000).map(String::from_utf8_lossy).collect();
+    let schema = fwf_schema(&names, options, &sample);
+
+    let n_threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    let offsets = if n_threads <= 1 || bytes.len() < 1024 * 1024 {
+        vec![0, bytes.len()]
+    } else {
+        line_chunk_offsets(bytes, n_threads, None)
+    };
+
     let results: Result<Vec<Vec<RecordBatch>>> = offsets
         .windows(2)
-        .enumerate()
         .collect::<Vec<_>>()
         .into_par_iter()
-        .map(|(id, window)| {
-            let start = window[0];
-            let end = window[1];
-            if start >= end {
-                return Ok(vec![]);
-            }
-
-            let chunk_bytes = &bytes[start..end];
-            let mut builder = ReaderBuilder::new(schema_arc.clone())
-                .with_batch_size(65536);
-            
-            // Only the first chunk should treat the first line as a header
-            if id == 0 {
-                builder = builder.with_header(true);
-            } else {
-                builder = builder.with_header(false);
-            }
-
-            let csv_reader = builder.build(chunk_bytes)?;
-            let chunk_batches: Vec<RecordBatch> = csv_reader
-                .collect::<std::result::Result<Vec<RecordBatch>, _>>()?;
-            
-            Ok(chunk_batches)
-        })
+        .map(|window| parse_fwf_chunk(&bytes[window[0]..window[1]], &schema, colspecs))
         .collect();
-
-    let batches = results?
+    let mut batches: Vec<RecordBatch> = results
+        .with_context(|| format!("Failed to parse fixed-width file: {}", source.name()))?
         .into_iter()
         .flatten()
         .collect();
+    if batches.is_empty() {
+        batches.push(RecordBatch::new_empty(schema));
+    }
+    Ok(DataFrame { batches })
+}
 
+/// Reads a fixed-width file in another encoding. Like `read_csv_transcoded`, the
+/// file is decoded twice as a stream, once for the type sample and once to parse it.
+fn read_fwf_transcoded(source: &Source, encoding: &'static Encoding, names: &[String], options: &FwfOptions) -> Result<DataFrame> {
+    let text_lines = || -> Result<_> {
+        Ok(open_text(source, encoding)?
+            .lines()
+            .filter(|line| line.as_ref().map_or(true, |line| !line.is_empty())))
+    };
+    let sample = text_lines()?
+        .take(1000)
+        .collect::<std::io::Result<Vec<String>>>()
+        .with_context(|| format!("Failed to read fixed-width file: {}", source.name()))?;
+    let schema = fwf_schema(names, options, &sample);
+
+    let mut batches = Vec::new();
+    let mut lines = text_lines()?;
+    loop {
+        let block = lines.by_ref()
+            .take(65536)
+            .collect::<std::io::Result<Vec<String>>>()
+            .with_context(|| format!("Failed to read fixed-width file: {}", source.name()))?;
+        if block.is_empty() {
+            break;
+        }
+        let batch = fwf_batch(&block, &schema, &options.colspecs)
+            .with_context(|| format!("Failed to parse fixed-width file: {}", source.name()))?;
+        batches.push(batch);
+    }
+    if batches.is_empty() {
+        batches.push(RecordBatch::new_empty(schema));
+    }
     Ok(DataFrame { batches })
 }
 
-pub fn to_csv(df: &DataFrame, path: &str) -> Result<()> {
-    let file = File::create(path).with_context(|| format!("Failed to create CSV file: {}", path))?;
-    let mut writer = Writer::new(file);
+/// Builds the schema from `names`, taking each field's type from `options.dtypes`
+/// or inferring it from the sample lines.
+fn fwf_schema<S: AsRef<str>>(names: &[String], options: &FwfOptions, sample: &[S]) -> Arc<Schema> {
+    let fields: Vec<Field> = names.iter().zip(&options.colspecs)
+        .map(|(name, spec)| {
+            let data_type = options.dtypes.get(name).cloned()
+                .unwrap_or_else(|| infer_fwf_type(sample.iter().map(|line| fwf_field(line.as_ref(), *spec))));
+            Field::new(name, data_type, true)
+        })
+        .collect();
+    Arc::new(Schema::new(fields))
+}
+
+fn fwf_lines(bytes: &[u8]) -> impl Iterator<Item = &[u8]> {
+    bytes.split(|&b| b == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .filter(|line| !line.is_empty())
+}
+
+/// Extracts the trimmed text of a field; positions count characters, so
+/// multi-byte UTF-8 lines are handled too.
+fn fwf_field(line: &str, (start, end): (usize, usize)) -> &str {
+    let field = if line.is_ascii() {
+        &line[start.min(line.len())..end.min(line.len())]
+    } else {
+        let byte_pos = |n: usize| line.char_indices().nth(n).map_or(line.len(), |(i, _)| i);
+        &line[byte_pos(start)..byte_pos(end)]
+    };
+    field.trim()
+}
+
+fn infer_fwf_type<'a>(values: impl Iterator<Item = &'a str>) -> DataType {
+    let values: Vec<&str> = values.filter(|v| !v.is_empty()).collect();
+    let all = |f: &dyn Fn(&str) -> bool| !values.is_empty() && values.iter().all(|v| f(v));
+    if all(&|v| v.parse::<i64>().is_ok()) {
+        DataType::Int64
+    } else if all(&|v| v.parse::<f64>().is_ok()) {
+        DataType::Float64
+    } else if all(&|v| v.eq_ignore_ascii_case("true") || v.eq_ignore_ascii_case("false")) {
+        DataType::Boolean
+    } else if all(&|v| Date32Type::parse(v).is_some()) {
+        DataType::Date32
+    } else if all(&|v| TimestampMicrosecondType::parse(v).is_some()) {
+        DataType::Timestamp(TimeUnit::Microsecond, None)
+    } else {
+        DataType::Utf8
+    }
+}
+
+fn parse_fwf_chunk(chunk: &[u8], schema: &Arc<Schema>, colspecs: &[(usize, usize)]) -> Result<Vec<RecordBatch>> {
+    let lines: Vec<&[u8]> = fwf_lines(chunk).collect();
+    lines.chunks(65536)
+        .map(|block| {
+            let block: Vec<_> = block.iter().map(|raw| String::from_utf8_lossy(raw)).collect();
+            fwf_batch(&block, schema, colspecs)
+        })
+        .collect()
+}
+
+fn fwf_batch<S: AsRef<str>>(lines: &[S], schema: &Arc<Schema>, colspecs: &[(usize, usize)]) -> Result<RecordBatch> {
+    let mut builders: Vec<StringBuilder> = colspecs.iter()
+        .map(|_| StringBuilder::with_capacity(lines.len(), lines.len() * 8))
+        .collect();
+    for line in lines {
+        for (builder, spec) in builders.iter_mut().zip(colspecs) {
+            match fwf_field(line.as_ref(), *spec) {
+                "" => builder.append_null(),
+                value => builder.append_value(value),
+            }
+        }
+    }
+    // Fail on values that don't parse instead of turning them into nulls
+    let strict = CastOptions { safe: false, ..Default::default() };
+    let columns = builders.iter_mut().zip(schema.fields())
+        .map(|(builder, field)| Ok(cast_with_options(&builder.finish(), field.data_type(), &strict)?))
+        .collect::<Result<Vec<ArrayRef>>>()?;
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
+}
+
+pub fn to_csv(df: &DataFrame, sink: Sink) -> Result<()> {
+    let name = sink.name().to_string();
+    let out = sink.create().with_context(|| format!("Failed to create CSV file: {}", name))?;
+    let mut writer = Writer::new(out);
     for batch in &df.batches {
         writer.write(batch)?;
     }
+    writer.into_inner().flush()?;
     Ok(())
 }
 
-pub fn read_parquet(path: &str) -> Result<DataFrame> {
-    let file = File::open(path).with_context(|| format!("Failed to open Parquet file: {}", path))?;
-    let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
+pub fn read_parquet(source: &Source) -> Result<DataFrame> {
+    match source {
+        Source::Path(path) => {
+            let file = File::open(path).with_context(|| format!("Failed to open Parquet file: {}", path))?;
+            read_parquet_chunks(file)
+        }
+        Source::Bytes(bytes) => read_parquet_chunks(bytes.clone()),
+    }
+}
+
+fn read_parquet_chunks<R: parquet::file::reader::ChunkReader + 'static>(input: R) -> Result<DataFrame> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(input)?;
     let reader = builder.build()?;
     let batches = reader
         .collect::<std::result::Result<Vec<RecordBatch>, _>>()
@@ -145,46 +445,97 @@ pub fn read_parquet(path: &str) -> Result<DataFrame> {
     Ok(DataFrame { batches })
 }
 
-pub fn to_parquet(df: &DataFrame, path: &str) -> Result<()> {
-    let file = File::create(path).with_context(|| format!("Failed to create Parquet file: {}", path))?;
+/// Reads an ORC file. With `columns`, only the stripes' streams for those
+/// columns are decoded, and the result follows the requested order.
+pub fn read_orc(source: &Source, columns: Option<&[String]>) -> Result<DataFrame> {
+    match source {
+        Source::Path(path) => {
+            let file = File::open(path).with_context(|| format!("Failed to open ORC file: {}", path))?;
+            read_orc_chunks(file, path, columns)
+        }
+        Source::Bytes(bytes) => read_orc_chunks(bytes.clone(), source.name(), columns),
+    }
+}
+
+fn read_orc_chunks<R: orc_rust::reader::ChunkReader>(input: R, name: &str, columns: Option<&[String]>) -> Result<DataFrame> {
+    let mut builder = OrcReaderBuilder::try_new(input).with_context(|| format!("Failed to read ORC file: {}", name))?;
+    if let Some(columns) = columns {
+        let root = builder.file_metadata().root_data_type();
+        for (i, name) in columns.iter().enumerate() {
+            if !root.children().iter().any(|c| c.name() == name) {
+                return Err(anyhow::anyhow!("Column not found in ORC file: {}", name));
+            }
+            if columns[..i].contains(name) {
+                return Err(anyhow::anyhow!("Column selected more than once: {}", name));
+            }
+        }
+        let mask = ProjectionMask::named_roots(root, columns);
+        builder = builder.with_projection(mask);
+    }
+    let reader = builder.build();
+    let schema = reader.schema();
+    let mut batches = reader
+        .collect::<std::result::Result<Vec<RecordBatch>, _>>()
+        .context("Failed to read ORC batches")?;
+    if batches.is_empty() {
+        batches.push(RecordBatch::new_empty(schema.clone()));
+    }
+
+    // The projection keeps the file's column order
+    if let Some(columns) = columns {
+        let indices = columns.iter()
+            .map(|name| schema.index_of(name))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        batches = batches.iter()
+            .map(|batch| batch.project(&indices))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+    }
+    Ok(DataFrame { batches })
+}
+
+pub fn to_parquet(df: &DataFrame, sink: Sink) -> Result<()> {
     let batches = &df.batches;
     if batches.is_empty() {
         return Err(anyhow::anyhow!("DataFrame is empty"));
     }
+    let name = sink.name().to_string();
+    let out = sink.create().with_context(|| format!("Failed to create Parquet file: {}", name))?;
     let schema = batches[0].schema();
-    let mut writer = ArrowWriter::try_new(file, schema, None)?;
+    let mut writer = ArrowWriter::try_new(out, schema, None)?;
     for batch in batches {
         writer.write(batch)?;
     }
-    writer.close()?;
+    writer.into_inner()?.flush()?;
     Ok(())
 }
 
-pub fn read_json(path: &str) -> Result<DataFrame> {
-    let file = File::open(path).with_context(|| format!("Failed to open JSON file: {}", path))?;
-    let mut reader = BufReader::new(file);
-    let (schema, _) = infer_json_schema(&mut reader, None)?;
-    reader.seek(SeekFrom::Start(0))?;
-    let json_reader = JsonReaderBuilder::new(Arc::new(schema)).build(reader)?;
+pub fn read_json(source: &Source, encoding: Option<&'static Encoding>) -> Result<DataFrame> {
+    let encoding = encoding.unwrap_or(UTF_8);
+    let open = || open_text(source, encoding).with_context(|| format!("Failed to open JSON file: {}", source.name()));
+    let (schema, _) = infer_json_schema(open()?, None)?;
+    let json_reader = JsonReaderBuilder::new(Arc::new(schema)).build(open()?)?;
     let batches = json_reader
         .collect::<std::result::Result<Vec<RecordBatch>, _>>()
         .context("Failed to read JSON batches")?;
     Ok(DataFrame { batches })
 }
 
-pub fn to_json(df: &DataFrame, path: &str) -> Result<()> {
-    let file = File::create(path).with_context(|| format!("Failed to create JSON file: {}", path))?;
-    let mut writer = LineDelimitedWriter::new(file);
+pub fn to_json(df: &DataFrame, sink: Sink) -> Result<()> {
+    let name = sink.name().to_string();
+    let out = sink.create().with_context(|| format!("Failed to create JSON file: {}", name))?;
+    let mut writer = LineDelimitedWriter::new(out);
     for batch in &df.batches {
         writer.write(batch)?;
     }
     writer.finish()?;
+    writer.into_inner().flush()?;
     Ok(())
 }
 
-pub fn read_excel(path: &str) -> Result<DataFrame> {
-    let mut workbook: Xlsx<_> = open_workbook(path).with_context(|| format!("Failed to open Excel file: {}", path))?;
-    let sheet_name = workbook.sheet_names().get(0).cloned().context("No sheets in workbook")?;
+pub fn read_excel(source: &Source) -> Result<DataFrame> {
+    let reader = source.open().with_context(|| format!("Failed to open Excel file: {}", source.name()))?;
+    let mut workbook = Xlsx::new(reader).with_context(|| format!("Failed to open Excel file: {}", source.name()))?;
+    let sheet_name = workbook.sheet_names().first().cloned().context("No sheets in workbook")?;
     let range = workbook.worksheet_range(&sheet_name).context("Failed to get sheet range")?;
     
     let mut rows = range.rows();
@@ -215,150 +566,536 @@ pub fn read_excel(path: &str) -> Result<DataFrame> {
     Ok(DataFrame { batches: vec![batch] })
 }
 
-pub fn to_excel(df: &DataFrame, path: &str) -> Result<()> {
+/// Layout options applied to a single worksheet by `write_excel`.
+#[derive(Clone, Default)]
+pub struct ExcelSheetOptions {
+    pub bold_header: bool,
+    pub freeze_panes: Option<(u32, u16)>,
+    pub autofilter: bool,
+    pub autofit: bool,
+    pub table_style: Option<String>,
+}
+
+pub fn to_excel(df: &DataFrame, sink: Sink) -> Result<()> {
+    write_excel(sink, &[("Sheet1", df, ExcelSheetOptions::default())])
+}
+
+pub fn write_excel(sink: Sink, sheets: &[(&str, &DataFrame, ExcelSheetOptions)]) -> Result<()> {
     let mut workbook = Workbook::new();
-    let worksheet = workbook.add_worksheet();
-    
-    if df.batches.is_empty() {
-        workbook.save(path)?;
-        return Ok(());
+
+    for (name, df, options) in sheets {
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(*name)
+            .with_context(|| format!("Invalid worksheet name: {}", name))?;
+        write_sheet(worksheet, df, options)
+            .with_context(|| format!("Failed to write worksheet: {}", name))?;
     }
 
-    let schema = df.batches[0].schema();
+    let name = sink.name().to_string();
+    let buffer = workbook.save_to_buffer().with_context(|| format!("Failed to save Excel file: {}", name))?;
+    let mut out = sink.create().with_context(|| format!("Failed to save Excel file: {}", name))?;
+    out.write_all(&buffer)?;
+    out.flush()?;
+    Ok(())
+}
+
+fn write_sheet(worksheet: &mut Worksheet, df: &DataFrame, options: &ExcelSheetOptions) -> Result<()> {
+    let schema = df.schema();
+    let n_cols = schema.fields().len() as u16;
+    let header_format = if options.bold_header {
+        Format::new().set_bold()
+    } else {
+        Format::new()
+    };
+    let date_format = Format::new().set_num_format("yyyy-mm-dd");
+    let datetime_format = Format::new().set_num_format("yyyy-mm-dd hh:mm:ss");
+
     // Write header
     for (i, field) in schema.fields().iter().enumerate() {
-        worksheet.write_string(0, i as u16, field.name())?;
+        worksheet.write_string_with_format(0, i as u16, field.name(), &header_format)?;
     }
 
-    let mut row_idx = 1;
+    let mut row_idx: u32 = 1;
     for batch in &df.batches {
+        // Convert each column once per batch rather than once per cell
+        let columns = batch.columns().iter()
+            .map(excel_column)
+            .collect::<Result<Vec<_>>>()?;
         for r in 0..batch.num_rows() {
-            for c in 0..batch.num_columns() {
-                let col = batch.column(c);
-                let string_col = arrow_cast::cast(col, &DataType::Utf8)?;
-                let string_array = string_col.as_any().downcast_ref::<StringArray>().unwrap();
-                worksheet.write_string(row_idx as u32, c as u16, string_array.value(r))?;
+            for (c, (original, column)) in batch.columns().iter().zip(&columns).enumerate() {
+                if original.is_null(r) {
+                    continue;
+                }
+                let c = c as u16;
+                match column {
+                    ExcelColumn::Number(values) if values.value(r).is_finite() => {
+                        worksheet.write_number(row_idx, c, values.value(r))?;
+                    }
+                    ExcelColumn::Boolean(values) => {
+                        worksheet.write_boolean(row_idx, c, values.value(r))?;
+                    }
+                    ExcelColumn::Date(values, with_time) => {
+                        // Excel serial dates count days from 1899-12-30
+                        let serial = 25569.0 + values.value(r) as f64 / 86_400_000.0;
+                        let format = if *with_time { &datetime_format } else { &date_format };
+                        match ExcelDateTime::from_serial_datetime(serial) {
+                            Ok(datetime) => worksheet.write_datetime_with_format(row_idx, c, datetime, format)?,
+                            // Excel has no dates before 1900 or after 9999
+                            Err(_) => worksheet.write_string(row_idx, c, excel_text(original, r)?)?,
+                        };
+                    }
+                    ExcelColumn::Text(values) => {
+                        worksheet.write_string(row_idx, c, values.value(r))?;
+                    }
+                    _ => {
+                        worksheet.write_string(row_idx, c, excel_text(original, r)?)?;
+                    }
+                }
             }
             row_idx += 1;
         }
     }
 
-    workbook.save(path)?;
-    Ok(())
-}
+    if n_cols > 0 {
+        // A table needs at least one data row below its header
+        let last_row = (row_idx - 1).max(1);
+        let last_col = n_cols - 1;
 
-pub fn read_sas(path: &str) -> Result<DataFrame> {
-    let file = File::open(path).with_context(|| format!("Failed to open SAS file: {}", path))?;
-    let mut reader = SasReader::from_reader(file)
-        .with_context(|| format!("Failed to parse SAS file: {}", path))?;
-    
-    // Collect all rows first
-    let mut rows_iter = reader.rows_named()?;
-    let mut all_rows: Vec<_> = Vec::new();
-    
-    while let Some(row_result) = rows_iter.next() {
-        let row = row_result?;
-        all_rows.push(row);
+        if let Some(style) = &options.table_style {
+            let columns: Vec<TableColumn> = schema.fields().iter()
+                .map(|field| TableColumn::new()
+                    .set_header(field.name())
+                    .set_header_format(&header_format))
+                .collect();
+            // Tables carry their own filter buttons, so the worksheet autofilter is not used
+            let table = Table::new()
+                .set_columns(&columns)
+                .set_style(parse_table_style(style)?)
+                .set_autofilter(options.autofilter);
+            worksheet.add_table(0, 0, last_row, last_col, &table)?;
+        } else if options.autofilter {
+            worksheet.autofilter(0, 0, row_idx - 1, last_col)?;
+        }
     }
-    
-    if all_rows.is_empty() {
-        return Err(anyhow::anyhow!("SAS file is empty"));
+
+    if let Some((row, col)) = options.freeze_panes {
+        worksheet.set_freeze_panes(row, col)?;
     }
-    
-    // Get column count from first row's actual values
-    let first_row = &all_rows[0];
-    let first_values = first_row.values();
-    let column_count = first_values.len();
-    
-    // Generate column names
-    let mut column_names = Vec::new();
-    for i in 0..column_count {
-        column_names.push(format!("col_{}", i));
+
+    if options.autofit {
+        worksheet.autofit();
     }
+
+    Ok(())
+}
+
+/// A column converted to the kind of cell it is written as.
+enum ExcelColumn {
+    Number(Float64Array),
+    Boolean(BooleanArray),
+    /// Milliseconds since the epoch, and whether the time of day is shown
+    Date(TimestampMillisecondArray, bool),
+    Text(StringArray),
+}
+
+fn excel_column(column: &ArrayRef) -> Result<ExcelColumn> {
+    Ok(match column.data_type() {
+        data_type if data_type.is_numeric() => {
+            ExcelColumn::Number(arrow_cast::cast(column, &DataType::Float64)?.as_primitive().clone())
+        }
+        DataType::Boolean => ExcelColumn::Boolean(column.as_boolean().clone()),
+        DataType::Date32 | DataType::Date64 => {
+            let millis = arrow_cast::cast(column, &DataType::Timestamp(TimeUnit::Millisecond, None))?;
+            ExcelColumn::Date(millis.as_primitive().clone(), false)
+        }
+        DataType::Timestamp(_, tz) => {
+            let millis = arrow_cast::cast(column, &DataType::Timestamp(TimeUnit::Millisecond, tz.clone()))?;
+            ExcelColumn::Date(millis.as_primitive().clone(), true)
+        }
+        _ => ExcelColumn::Text(arrow_cast::cast(column, &DataType::Utf8)?.as_string().clone()),
+    })
+}
+
+fn excel_text(column: &ArrayRef, row: usize) -> Result<String> {
+    Ok(ArrayFormatter::try_new(column.as_ref(), &FormatOptions::default())?.value(row).to_string())
+}
+
+const TABLE_STYLES: [TableStyle; 61] = [
+    TableStyle::None,
+    TableStyle::Light1, TableStyle::Light2, TableStyle::Light3, TableStyle::Light4,
+    TableStyle::Light5, TableStyle::Light6, TableStyle::Light7, TableStyle::Light8,
+    TableStyle::Light9, TableStyle::Light10, TableStyle::Light11, TableStyle::Light12,
+    TableStyle::Light13, TableStyle::Light14, TableStyle::Light15, TableStyle::Light16,
+    TableStyle::Light17, TableStyle::Light18, TableStyle::Light19, TableStyle::Light20,
+    TableStyle::Light21,
+    TableStyle::Medium1, TableStyle::Medium2, TableStyle::Medium3, TableStyle::Medium4,
+    TableStyle::Medium5, TableStyle::Medium6, TableStyle::Medium7, TableStyle::Medium8,
+    TableStyle::Medium9, TableStyle::Medium10, TableStyle::Medium11, TableStyle::Medium12,
+    TableStyle::Medium13, TableStyle::Medium14, TableStyle::Medium15, TableStyle::Medium16,
+    TableStyle::Medium17, TableStyle::Medium18, TableStyle::Medium19, TableStyle::Medium20,
+    TableStyle::Medium21, TableStyle::Medium22, TableStyle::Medium23, TableStyle::Medium24,
+    TableStyle::Medium25, TableStyle::Medium26, TableStyle::Medium27, TableStyle::Medium28,
+    TableStyle::Dark1, TableStyle::Dark2, TableStyle::Dark3, TableStyle::Dark4,
+    TableStyle::Dark5, TableStyle::Dark6, TableStyle::Dark7, TableStyle::Dark8,
+    TableStyle::Dark9, TableStyle::Dark10, TableStyle::Dark11,
+];
+
+/// Accepts Excel style names such as "Medium9" or "TableStyleMedium9".
+fn parse_table_style(name: &str) -> Result<TableStyle> {
+    let normalized = name.trim().to_lowercase();
+    let normalized = normalized.strip_prefix("tablestyle").unwrap_or(&normalized);
+    TABLE_STYLES.iter()
+        .find(|style| style.to_string()["TableStyle".len()..].to_lowercase() == normalized)
+        .copied()
+        .ok_or_else(|| anyhow::anyhow!("Unknown Excel table style: {}", name))
+}
+
+/// Number of SAS rows decoded into each RecordBatch unless the caller asks otherwise.
+pub const SAS_BATCH_SIZE: usize = 65536;
+
+/// Column projection, row window and chunking options for `read_sas`.
+#[derive(Clone, Default)]
+pub struct SasReadOptions {
+    pub columns: Option<Vec<String>>,
+    pub n_rows: Option<usize>,
+    pub skip_rows: usize,
+    pub chunk_size: Option<usize>,
+}
+
+pub fn read_sas(source: &Source, options: &SasReadOptions) -> Result<DataFrame> {
+    let reader = SasBatchReader::try_new(source, options)?;
+    let schema = reader.schema();
+    let mut batches = reader
+        .collect::<Result<Vec<RecordBatch>>>()
+        .with_context(|| format!("Failed to read SAS rows: {}", source.name()))?;
     
-    // Infer schema from first row using values()
-    let mut fields = Vec::new();
+    // The schema comes from metadata, so a dataset without rows still keeps its columns
+    if batches.is_empty() {
+        batches.push(RecordBatch::new_empty(schema));
+    }
     
-    for (i, cell) in first_values.iter().enumerate() {
-        let col_name = &column_names[i];
+    Ok(DataFrame { batches })
+}
+
+/// Streams a SAS7BDAT file as RecordBatches of at most `chunk_size` rows, so only
+/// one chunk of decoded values is held in memory at a time.
+pub struct SasBatchReader {
+    rows: OwnedRowIterator<SourceReader>,
+    schema: Arc<Schema>,
+    projection: Vec<usize>,
+    batch_size: usize,
+    skip_rows: usize,
+    remaining: Option<usize>,
+}
+
+impl SasBatchReader {
+    pub fn try_new(source: &Source, options: &SasReadOptions) -> Result<Self> {
+        let batch_size = options.chunk_size.unwrap_or(SAS_BATCH_SIZE);
+        if batch_size == 0 {
+            return Err(anyhow::anyhow!("SAS chunk size must be greater than zero"));
+        }
+        let file = source.open().with_context(|| format!("Failed to open SAS file: {}", source.name()))?;
+        let reader = SasReader::from_reader(file)
+            .with_context(|| format!("Failed to parse SAS file: {}", source.name()))?;
+        let variables = reader.metadata().variables.clone();
+        let (mut file, layout) = reader.into_parts();
         
-        let data_type = match cell {
-            CellValue::Float(_) | CellValue::Int32(_) | CellValue::Int64(_) => DataType::Float64,
-            CellValue::Str(_) | CellValue::NumericString(_) | CellValue::Bytes(_) => DataType::Utf8,
-            CellValue::DateTime(_) | CellValue::Date(_) => DataType::Timestamp(arrow_schema::TimeUnit::Millisecond, None),
-            CellValue::Time(_) => DataType::Utf8,
-            CellValue::Missing(_) => DataType::Utf8,
+        let projection = match &options.columns {
+            Some(names) => {
+                let mut projection = Vec::with_capacity(names.len());
+                for name in names {
+                    let idx = variables.iter()
+                        .position(|v| v.name.trim_end() == name.trim_end())
+                        .with_context(|| format!("Column not found in SAS file: {}", name))?;
+                    if projection.contains(&idx) {
+                        return Err(anyhow::anyhow!("Column selected more than once: {}", name));
+                    }
+                    projection.push(idx);
+                }
+                projection
+            }
+            None => (0..variables.len()).collect(),
         };
         
-        fields.push(Field::new(col_name.clone(), data_type, true));
+        // Column types come from the SAS column descriptors, which are also what the
+        // row decoder uses to produce dates, datetimes and times
+        let fields: Vec<Field> = projection.iter()
+            .map(|&idx| sas_field(&variables[idx], sas_data_type(layout.columns[idx].kind)))
+            .collect();
+        let schema = Arc::new(Schema::new(fields));
+        
+        file.seek(SeekFrom::Start(0))?;
+        let rows = RowIteratorCore::new(file, Box::new(layout))
+            .with_context(|| format!("Failed to read SAS rows: {}", source.name()))?;
+        
+        Ok(SasBatchReader {
+            rows,
+            schema,
+            projection,
+            batch_size,
+            skip_rows: options.skip_rows,
+            remaining: options.n_rows,
+        })
     }
-    
-    let schema = Arc::new(Schema::new(fields));
-    
-    // Build column arrays
-    let mut column_builders: Vec<Vec<Option<String>>> = vec![Vec::new(); column_count];
-    let mut numeric_builders: Vec<Vec<Option<f64>>> = vec![Vec::new(); column_count];
-    let mut timestamp_builders: Vec<Vec<Option<i64>>> = vec![Vec::new(); column_count];
-    
-    for row in &all_rows {
-        let values = row.values();
+
+    pub fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    pub fn next_batch(&mut self) -> Result<Option<RecordBatch>> {
+        // Skipped rows are paged through without decoding any of their cells
+        while self.skip_rows > 0 {
+            if self.rows.try_next_streaming(&mut |_| Ok(()))?.is_none() {
+                return Ok(None);
+            }
+            self.skip_rows -= 1;
+        }
         
-        for (i, cell) in values.iter().enumerate() {
-            match &schema.fields()[i].data_type() {
-                DataType::Float64 => {
-                    let value = match cell {
-                        CellValue::Float(f) => Some(*f),
-                        CellValue::Int32(val) => Some(*val as f64),
-                        CellValue::Int64(val) => Some(*val as f64),
-                        CellValue::Missing(_) => None,
-                        _ => None,
-                    };
-                    numeric_builders[i].push(value);
-                }
-                DataType::Timestamp(_, _) => {
-                    let value = match cell {
-                        CellValue::DateTime(dt) => Some(dt.unix_timestamp() * 1000),
-                        CellValue::Date(dt) => Some(dt.unix_timestamp() * 1000),
-                        CellValue::Missing(_) => None,
-                        _ => None,
-                    };
-                    timestamp_builders[i].push(value);
-                }
-                DataType::Utf8 => {
-                    let value = match cell {
-                        CellValue::Str(s) => Some(s.to_string()),
-                        CellValue::NumericString(s) => Some(s.to_string()),
-                        CellValue::Bytes(b) => Some(String::from_utf8_lossy(b).to_string()),
-                        CellValue::Time(d) => Some(format!("{}", d.whole_seconds())),
-                        CellValue::Missing(_) => None,
-                        _ => Some(format!("{:?}", cell)),
-                    };
-                    column_builders[i].push(value);
-                }
-                _ => {}
+        let limit = self.remaining.map_or(self.batch_size, |n| n.min(self.batch_size));
+        if limit == 0 {
+            return Ok(None);
+        }
+        
+        let capacity = limit.min(4096);
+        let mut builders: Vec<SasColumnBuilder> = self.schema.fields().iter()
+            .map(|field| SasColumnBuilder::new(field.data_type(), capacity))
+            .collect();
+        let projection = &self.projection;
+        let mut n_rows = 0;
+        
+        // Only the projected cells of each row are decoded
+        let mut append_row = |row: StreamingRow<'_, '_>| -> sas7bdat::Result<()> {
+            for (builder, &idx) in builders.iter_mut().zip(projection) {
+                builder.append(&row.cell(idx)?.decode_value()?);
             }
+            Ok(())
+        };
+        while n_rows < limit {
+            if self.rows.try_next_streaming(&mut append_row)?.is_none() {
+                break;
+            }
+            n_rows += 1;
+        }
+        
+        if n_rows == 0 {
+            return Ok(None);
+        }
+        if let Some(remaining) = self.remaining.as_mut() {
+            *remaining -= n_rows;
         }
+        
+        let arrays: Vec<ArrayRef> = builders.iter_mut().map(SasColumnBuilder::finish).collect();
+        Ok(Some(RecordBatch::try_new(self.schema.clone(), arrays)?))
     }
-    
-    // Create Arrow arrays
-    let mut arrays: Vec<Arc<dyn Array>> = Vec::new();
-    for i in 0..column_count {
-        let array: Arc<dyn Array> = match &schema.fields()[i].data_type() {
-            DataType::Float64 => {
-                Arc::new(Float64Array::from(numeric_builders[i].clone()))
+}
+
+impl Iterator for SasBatchReader {
+    type Item = Result<RecordBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_batch().transpose()
+    }
+}
+
+fn sas_data_type(kind: ColumnKind) -> DataType {
+    match kind {
+        ColumnKind::Character => DataType::Utf8,
+        ColumnKind::Numeric(NumericKind::Double) => DataType::Float64,
+        ColumnKind::Numeric(NumericKind::Date) => DataType::Date32,
+        ColumnKind::Numeric(NumericKind::DateTime) => DataType::Timestamp(TimeUnit::Millisecond, None),
+        ColumnKind::Numeric(NumericKind::Time) => DataType::Time64(TimeUnit::Microsecond),
+    }
+}
+
+// Days and seconds between the SAS epoch (1960-01-01) and the Unix epoch
+pub(crate) const SAS_EPOCH_OFFSET_DAYS: i32 = 3653;
+pub(crate) const SAS_EPOCH_OFFSET_SECONDS: i64 = 3653 * 86_400;
+
+// SAS stores dates, datetimes and times as doubles. Shared by the SAS7BDAT and
+// XPORT readers so both decode a value the same way: dates are floored to the day,
+// datetimes and times rounded to the nearest millisecond and microsecond.
+pub(crate) fn sas_date_days(days: f64) -> i32 {
+    days.floor() as i32 - SAS_EPOCH_OFFSET_DAYS
+}
+
+pub(crate) fn sas_datetime_millis(secs: f64) -> i64 {
+    ((secs - SAS_EPOCH_OFFSET_SECONDS as f64) * 1000.0).round() as i64
+}
+
+pub(crate) fn sas_time_micros(secs: f64) -> i64 {
+    (secs * 1_000_000.0).round() as i64
+}
+
+enum SasColumnBuilder {
+    Float64(Float64Builder),
+    Date32(Date32Builder),
+    Timestamp(TimestampMillisecondBuilder),
+    Time64(Time64MicrosecondBuilder),
+    Utf8(StringBuilder),
+}
+
+impl SasColumnBuilder {
+    fn new(data_type: &DataType, capacity: usize) -> Self {
+        match data_type {
+            DataType::Float64 => SasColumnBuilder::Float64(Float64Builder::with_capacity(capacity)),
+            DataType::Date32 => SasColumnBuilder::Date32(Date32Builder::with_capacity(capacity)),
+            DataType::Timestamp(_, _) => SasColumnBuilder::Timestamp(TimestampMillisecondBuilder::with_capacity(capacity)),
+            DataType::Time64(_) => SasColumnBuilder::Time64(Time64MicrosecondBuilder::with_capacity(capacity)),
+            _ => SasColumnBuilder::Utf8(StringBuilder::with_capacity(capacity, capacity * 8)),
+        }
+    }
+
+    // The decoder falls back to a plain number when a date, datetime or time is out of
+    // range for the `time` crate, so numeric cells are converted from SAS units here
+    fn append(&mut self, cell: &CellValue<'_>) {
+        match self {
+            SasColumnBuilder::Float64(builder) => builder.append_option(sas_number(cell)),
+            SasColumnBuilder::Date32(builder) => {
+                let value = match cell {
+                    CellValue::Date(dt) | CellValue::DateTime(dt) => Some(dt.unix_timestamp().div_euclid(86_400) as i32),
+                    _ => sas_number(cell).map(sas_date_days),
+                };
+                builder.append_option(value);
             }
-            DataType::Timestamp(_, _) => {
-                Arc::new(TimestampMillisecondArray::from(timestamp_builders[i].clone()))
+            SasColumnBuilder::Timestamp(builder) => {
+                let value = match cell {
+                    CellValue::DateTime(dt) | CellValue::Date(dt) => Some((dt.unix_timestamp_nanos() + 500_000).div_euclid(1_000_000) as i64),
+                    _ => sas_number(cell).map(sas_datetime_millis),
+                };
+                builder.append_option(value);
             }
-            DataType::Utf8 => {
-                Arc::new(StringArray::from(column_builders[i].clone()))
+            SasColumnBuilder::Time64(builder) => {
+                let value = match cell {
+                    CellValue::Time(d) => Some((d.whole_nanoseconds() + 500).div_euclid(1000) as i64),
+                    _ => sas_number(cell).map(sas_time_micros),
+                };
+                builder.append_option(value);
             }
-            _ => Arc::new(StringArray::from(column_builders[i].clone())),
-        };
-        arrays.push(array);
+            SasColumnBuilder::Utf8(builder) => {
+                match cell {
+                    CellValue::Str(s) => builder.append_value(s),
+                    CellValue::NumericString(s) => builder.append_value(s),
+                    CellValue::Bytes(b) => builder.append_value(String::from_utf8_lossy(b)),
+                    _ => builder.append_null(),
+                }
+            }
+        }
     }
-    
-    let batch = RecordBatch::try_new(schema, arrays)?;
-    Ok(DataFrame { batches: vec![batch] })
+
+    fn finish(&mut self) -> ArrayRef {
+        match self {
+            SasColumnBuilder::Float64(builder) => Arc::new(builder.finish()),
+            SasColumnBuilder::Date32(builder) => Arc::new(builder.finish()),
+            SasColumnBuilder::Timestamp(builder) => Arc::new(builder.finish()),
+            SasColumnBuilder::Time64(builder) => Arc::new(builder.finish()),
+            SasColumnBuilder::Utf8(builder) => Arc::new(builder.finish()),
+        }
+    }
+}
+
+fn sas_number(cell: &CellValue<'_>) -> Option<f64> {
+    match cell {
+        CellValue::Float(f) => Some(*f),
+        CellValue::Int32(val) => Some(*val as f64),
+        CellValue::Int64(val) => Some(*val as f64),
+        CellValue::NumericString(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+pub fn read_xpt(source: &Source) -> Result<DataFrame> {
+    let data = source.map().with_context(|| format!("Failed to open XPORT file: {}", source.name()))?;
+    xport::read(&data).with_context(|| format!("Failed to read XPORT file: {}", source.name()))
+}
+
+pub fn to_xpt(df: &DataFrame, sink: Sink, name: Option<&str>, version: u8) -> Result<()> {
+    let version = XportVersion::from_number(version)?;
+    // SAS names the member after the file unless told otherwise
+    let member = name.or(sink.stem()).unwrap_or("DATA").to_string();
+    let name = sink.name().to_string();
+    let mut writer = sink.create().with_context(|| format!("Failed to create XPORT file: {}", name))?;
+    xport::write(df, &mut writer, &member, version)
+        .with_context(|| format!("Failed to write XPORT file: {}", name))
+}
+
+pub fn read_stata(source: &Source, value_labels: bool) -> Result<DataFrame> {
+    let data = source.map().with_context(|| format!("Failed to open Stata file: {}", source.name()))?;
+    stata::read(&data, value_labels).with_context(|| format!("Failed to read Stata file: {}", source.name()))
+}
+
+pub fn read_spss(source: &Source, value_labels: bool) -> Result<DataFrame> {
+    let data = source.map().with_context(|| format!("Failed to open SPSS file: {}", source.name()))?;
+    spss::read(&data, value_labels).with_context(|| format!("Failed to read SPSS file: {}", source.name()))
 }
 
+pub fn read_avro(source: &Source) -> Result<DataFrame> {
+    let reader = source.open().with_context(|| format!("Failed to open Avro file: {}", source.name()))?;
+    avro::read(reader).with_context(|| format!("Failed to read Avro file: {}", source.name()))
+}
+
+pub fn to_avro(df: &DataFrame, sink: Sink, codec: &str) -> Result<()> {
+    let codec = avro::parse_codec(codec)?;
+    // Like XPORT, the record is named after the file
+    let record = sink.stem().unwrap_or("record").to_string();
+    let name = sink.name().to_string();
+    let out = sink.create().with_context(|| format!("Failed to create Avro file: {}", name))?;
+    avro::write(df, out, &record, codec)
+        .with_context(|| format!("Failed to write Avro file: {}", name))
+}
+
+pub fn read_sqlite(source: &Source, query: &str) -> Result<DataFrame> {
+    let conn = match source {
+        // Opened read-only so a mistyped path isn't silently created as an empty database
+        Source::Path(path) => Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+            .with_context(|| format!("Failed to open SQLite database: {}", path))?,
+        Source::Bytes(bytes) => {
+            let mut conn = Connection::open_in_memory()?;
+            conn.deserialize_read_exact(MAIN_DB, &bytes[..], bytes.len(), true)
+                .context("Failed to open SQLite database from bytes")?;
+            conn
+        }
+    };
+    // SQL errors are more useful to the caller than a generic context message
+    sqlite::read(&conn, query)
+}
+
+/// Writes the DataFrame to `table`. A file-like target receives a new database
+/// holding just that table, built in memory.
+pub fn to_sqlite(df: &DataFrame, sink: Sink, table: &str, if_exists: &str) -> Result<()> {
+    let if_exists = IfExists::parse(if_exists)?;
+    match sink {
+        Sink::Path(db_path) => {
+            let mut conn = Connection::open(&db_path).with_context(|| format!("Failed to open SQLite database: {}", db_path))?;
+            sqlite::write(df, &mut conn, table, if_exists)
+        }
+        sink => {
+            let mut conn = Connection::open_in_memory()?;
+            sqlite::write(df, &mut conn, table, if_exists)?;
+            let data = conn.serialize(MAIN_DB)?;
+            let mut out = sink.create()?;
+            out.write_all(&data)?;
+            out.flush()?;
+            Ok(())
+        }
+    }
+}
+
+/// Builds an Arrow field for a SAS variable, keeping its label, format and
+/// storage length as field metadata.
+fn sas_field(variable: &SasVariable, data_type: DataType) -> Field {
+    let mut metadata = HashMap::new();
+    if let Some(label) = variable.label.as_deref().map(str::trim).filter(|l| !l.is_empty()) {
+        metadata.insert("label".to_string(), label.to_string());
+    }
+    if let Some(format) = &variable.format {
+        let name = format.name.trim();
+        let width = format.width.filter(|w| *w > 0).map(|w| w.to_string()).unwrap_or_default();
+        let decimals = format.decimals.filter(|d| *d > 0).map(|d| d.to_string()).unwrap_or_default();
+        if !name.is_empty() || !width.is_empty() {
+            metadata.insert("format".to_string(), format!("{}{}.{}", name, width, decimals));
+        }
+    }
+    metadata.insert("length".to_string(), variable.storage_width.to_string());
+
+    Field::new(variable.name.trim_end(), data_type, true).with_metadata(metadata)
+}
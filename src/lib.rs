@@ -1,42 +1,292 @@
+// pyo3 0.22's #[pymethods] expansion converts PyErr into PyErr, which clippy flags
+#![allow(clippy::useless_conversion)]
+
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::collections::HashMap;
 
 mod dataframe;
 mod io;
+mod xport;
+mod stata;
+mod spss;
+mod avro;
+mod sqlite;
+mod source;
+mod pyvalues;
+mod series;
+mod expr;
+mod query;
 
 use dataframe::DataFrame;
+use series::Series;
+use expr::{Expr, Node, Then, When};
+use io::ExcelSheetOptions;
+use source::{Sink, Source};
+
+#[pyfunction]
+#[pyo3(signature = (path, encoding=None, member=None))]
+fn read_csv(path: Source, encoding: Option<String>, member: Option<String>) -> PyResult<DataFrame> {
+    let encoding = parse_encoding(encoding.as_deref())?;
+    let path = match member {
+        Some(member) => path.zip_member(&member)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))?,
+        None => path,
+    };
+    io::read_csv(&path, encoding).map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
+}
 
 #[pyfunction]
-fn read_csv(path: String) -> PyResult<DataFrame> {
-    io::read_csv(&path).map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
+#[pyo3(signature = (path, widths=None, colspecs=None, names=None, dtypes=None, encoding=None))]
+fn read_fwf(
+    path: Source,
+    widths: Option<Vec<usize>>,
+    colspecs: Option<Vec<(usize, usize)>>,
+    names: Option<Vec<String>>,
+    dtypes: Option<HashMap<String, String>>,
+    encoding: Option<String>,
+) -> PyResult<DataFrame> {
+    let encoding = parse_encoding(encoding.as_deref())?;
+    let value_error = |msg: String| PyErr::new::<pyo3::exceptions::PyValueError, _>(msg);
+    let colspecs = match (widths, colspecs) {
+        (Some(widths), None) => widths.iter()
+            .scan(0, |start, &width| {
+                let spec = (*start, *start + width);
+                *start += width;
+                Some(spec)
+            })
+            .collect(),
+        (None, Some(colspecs)) => colspecs,
+        _ => return Err(value_error("Exactly one of widths or colspecs must be given".to_string())),
+    };
+    if let Some((start, end)) = colspecs.iter().find(|(start, end)| end <= start) {
+        return Err(value_error(format!("Invalid column spec: ({}, {})", start, end)));
+    }
+    let dtypes = dtypes.unwrap_or_default().into_iter()
+        .map(|(name, dtype)| Ok((name, io::parse_dtype(&dtype).map_err(|e| value_error(e.to_string()))?)))
+        .collect::<PyResult<HashMap<_, _>>>()?;
+    let options = io::FwfOptions { colspecs, names, dtypes, encoding };
+    io::read_fwf(&path, &options).map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
 }
 
 #[pyfunction]
-fn read_parquet(path: String) -> PyResult<DataFrame> {
+fn read_parquet(path: Source) -> PyResult<DataFrame> {
     io::read_parquet(&path).map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
 }
 
 #[pyfunction]
-fn read_json(path: String) -> PyResult<DataFrame> {
-    io::read_json(&path).map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
+#[pyo3(signature = (path, columns=None))]
+fn read_orc(path: Source, columns: Option<Vec<String>>) -> PyResult<DataFrame> {
+    io::read_orc(&path, columns.as_deref()).map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
 }
 
 #[pyfunction]
-fn read_excel(path: String) -> PyResult<DataFrame> {
+#[pyo3(signature = (path, encoding=None))]
+fn read_json(path: Source, encoding: Option<String>) -> PyResult<DataFrame> {
+    let encoding = parse_encoding(encoding.as_deref())?;
+    io::read_json(&path, encoding).map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
+}
+
+#[pyfunction]
+fn read_excel(path: Source) -> PyResult<DataFrame> {
     io::read_excel(&path).map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
 }
 
 #[pyfunction]
-fn read_sas(path: String) -> PyResult<DataFrame> {
-    io::read_sas(&path).map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
+#[pyo3(signature = (path, columns=None, n_rows=None, skip_rows=0, chunk_size=None))]
+fn read_sas(path: Source, columns: Option<Vec<String>>, n_rows: Option<usize>, skip_rows: usize, chunk_size: Option<usize>) -> PyResult<DataFrame> {
+    let options = io::SasReadOptions { columns, n_rows, skip_rows, chunk_size };
+    io::read_sas(&path, &options).map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
+}
+
+#[pyfunction]
+fn read_xpt(path: Source) -> PyResult<DataFrame> {
+    io::read_xpt(&path).map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
+}
+
+#[pyfunction]
+#[pyo3(signature = (path, value_labels=true))]
+fn read_stata(path: Source, value_labels: bool) -> PyResult<DataFrame> {
+    io::read_stata(&path, value_labels).map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
+}
+
+#[pyfunction]
+#[pyo3(signature = (path, value_labels=true))]
+fn read_spss(path: Source, value_labels: bool) -> PyResult<DataFrame> {
+    io::read_spss(&path, value_labels).map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
+}
+
+#[pyfunction]
+fn read_avro(path: Source) -> PyResult<DataFrame> {
+    io::read_avro(&path).map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
+}
+
+#[pyfunction]
+fn read_sqlite(db_path: Source, query: String) -> PyResult<DataFrame> {
+    io::read_sqlite(&db_path, &query).map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
+}
+
+/// Wraps the record batches of a pyarrow Table, polars DataFrame or any other object
+/// implementing the Arrow PyCapsule interface, without copying them.
+#[pyfunction]
+fn from_arrow(obj: &Bound<'_, PyAny>) -> PyResult<DataFrame> {
+    DataFrame::from_arrow_object(obj)
+}
+
+/// Builds a DataFrame from a list of dicts, one per row.
+#[pyfunction]
+#[pyo3(signature = (records, schema=None))]
+fn from_records(records: &Bound<'_, PyAny>, schema: Option<HashMap<String, String>>) -> PyResult<DataFrame> {
+    pyvalues::from_records(records, &pyvalues::parse_dtypes(schema)?)
+}
+
+/// Builds a DataFrame from a 1-D or 2-D NumPy array (or anything exposing a buffer or `tolist()`).
+#[pyfunction]
+#[pyo3(signature = (array, columns=None, schema=None))]
+fn from_numpy(array: &Bound<'_, PyAny>, columns: Option<Vec<String>>, schema: Option<HashMap<String, String>>) -> PyResult<DataFrame> {
+    pyvalues::from_numpy(array, columns, &pyvalues::parse_dtypes(schema)?)
+}
+
+/// A reference to a column, for use in expressions.
+#[pyfunction]
+fn col(name: String) -> Expr {
+    Expr { node: Node::Column(name) }
+}
+
+/// A literal value, for use in expressions.
+#[pyfunction]
+fn lit(value: Node) -> Expr {
+    Expr { node: value }
+}
+
+/// Starts a conditional expression: `when(cond).then(a).when(cond2).then(b).otherwise(c)`.
+#[pyfunction]
+fn when(condition: Node) -> When {
+    When::new(condition)
+}
+
+/// Lists the files in a zip archive, so they can be passed to the readers as `member`
+/// or in the `archive.zip!member` form.
+#[pyfunction]
+fn zip_members(path: Source) -> PyResult<Vec<String>> {
+    path.zip_members().map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
+}
+
+/// Iterates over a SAS7BDAT file one chunk of rows at a time, yielding a DataFrame per chunk.
+#[pyclass(unsendable)]
+struct SasChunkIterator {
+    reader: io::SasBatchReader,
+}
+
+#[pymethods]
+impl SasChunkIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> PyResult<Option<DataFrame>> {
+        let batch = self.reader.next_batch()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))?;
+        Ok(batch.map(|batch| DataFrame { batches: vec![batch] }))
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (path, chunk_size=io::SAS_BATCH_SIZE, columns=None, n_rows=None, skip_rows=0))]
+fn iter_sas(path: Source, chunk_size: usize, columns: Option<Vec<String>>, n_rows: Option<usize>, skip_rows: usize) -> PyResult<SasChunkIterator> {
+    let options = io::SasReadOptions { columns, n_rows, skip_rows, chunk_size: Some(chunk_size) };
+    let reader = io::SasBatchReader::try_new(&path, &options)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))?;
+    Ok(SasChunkIterator { reader })
+}
+
+#[pyfunction]
+#[pyo3(signature = (path, sheets, bold_header=false, freeze_panes=None, autofilter=false, autofit=false, table_style=None, sheet_options=None))]
+#[allow(clippy::too_many_arguments)]
+fn write_excel(
+    path: Sink,
+    sheets: &Bound<'_, PyDict>,
+    bold_header: bool,
+    freeze_panes: Option<(u32, u16)>,
+    autofilter: bool,
+    autofit: bool,
+    table_style: Option<String>,
+    sheet_options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<()> {
+    let defaults = ExcelSheetOptions { bold_header, freeze_panes, autofilter, autofit, table_style };
+
+    let mut frames = Vec::new();
+    for (name, df) in sheets.iter() {
+        let name: String = name.extract()?;
+        let df: PyRef<DataFrame> = df.extract()?;
+        let overrides = match sheet_options {
+            Some(opts) => opts.get_item(&name)?,
+            None => None,
+        };
+        let options = match overrides {
+            Some(overrides) => sheet_options_from_dict(&defaults, overrides.downcast::<PyDict>()?)?,
+            None => defaults.clone(),
+        };
+        frames.push((name, df, options));
+    }
+
+    let sheets: Vec<(&str, &DataFrame, ExcelSheetOptions)> = frames
+        .iter()
+        .map(|(name, df, options)| (name.as_str(), &**df, options.clone()))
+        .collect();
+    io::write_excel(path, &sheets).map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
+}
+
+fn parse_encoding(label: Option<&str>) -> PyResult<Option<&'static encoding_rs::Encoding>> {
+    label.map(io::parse_encoding)
+        .transpose()
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+}
+
+fn sheet_options_from_dict(defaults: &ExcelSheetOptions, dict: &Bound<'_, PyDict>) -> PyResult<ExcelSheetOptions> {
+    let mut options = defaults.clone();
+    for (key, value) in dict.iter() {
+        let key: String = key.extract()?;
+        match key.as_str() {
+            "bold_header" => options.bold_header = value.extract()?,
+            "freeze_panes" => options.freeze_panes = value.extract()?,
+            "autofilter" => options.autofilter = value.extract()?,
+            "autofit" => options.autofit = value.extract()?,
+            "table_style" => options.table_style = value.extract()?,
+            _ => return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Unknown sheet option: {}", key))),
+        }
+    }
+    Ok(options)
 }
 
 #[pymodule]
 fn grizzly(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<DataFrame>()?;
+    m.add_class::<Series>()?;
+    m.add_class::<Expr>()?;
+    m.add_class::<When>()?;
+    m.add_class::<Then>()?;
+    m.add_class::<SasChunkIterator>()?;
     m.add_function(wrap_pyfunction!(read_csv, m)?)?;
+    m.add_function(wrap_pyfunction!(read_fwf, m)?)?;
     m.add_function(wrap_pyfunction!(read_parquet, m)?)?;
     m.add_function(wrap_pyfunction!(read_json, m)?)?;
     m.add_function(wrap_pyfunction!(read_excel, m)?)?;
     m.add_function(wrap_pyfunction!(read_sas, m)?)?;
+    m.add_function(wrap_pyfunction!(iter_sas, m)?)?;
+    m.add_function(wrap_pyfunction!(read_xpt, m)?)?;
+    m.add_function(wrap_pyfunction!(read_stata, m)?)?;
+    m.add_function(wrap_pyfunction!(read_spss, m)?)?;
+    m.add_function(wrap_pyfunction!(read_avro, m)?)?;
+    m.add_function(wrap_pyfunction!(read_orc, m)?)?;
+    m.add_function(wrap_pyfunction!(read_sqlite, m)?)?;
+    m.add_function(wrap_pyfunction!(from_arrow, m)?)?;
+    m.add_function(wrap_pyfunction!(from_records, m)?)?;
+    m.add_function(wrap_pyfunction!(from_numpy, m)?)?;
+    m.add_function(wrap_pyfunction!(col, m)?)?;
+    m.add_function(wrap_pyfunction!(lit, m)?)?;
+    m.add_function(wrap_pyfunction!(when, m)?)?;
+    m.add_function(wrap_pyfunction!(zip_members, m)?)?;
+    m.add_function(wrap_pyfunction!(write_excel, m)?)?;
     Ok(())
 }
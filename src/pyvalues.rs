@@ -0,0 +1,589 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use arrow::record_batch::RecordBatch;
+use arrow_array::builder::{
+    BinaryBuilder, BooleanBuilder, Date32Builder, Float64Builder, Int64Builder, StringBuilder,
+    Time64MicrosecondBuilder, TimestampMicrosecondBuilder,
+};
+use arrow_array::cast::AsArray;
+use arrow_array::types::{
+    ArrowPrimitiveType, Date32Type, DurationMicrosecondType, Float16Type, Float32Type, Float64Type,
+    Int16Type, Int32Type, Int64Type, Int8Type, Time64MicrosecondType, TimestampMicrosecondType,
+    UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use arrow_array::{Array, ArrayRef, NullArray, PrimitiveArray, StringArray};
+use arrow_cast::{cast, cast_with_options, CastOptions};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use chrono::{Datelike, NaiveDate, Timelike};
+use pyo3::buffer::{Element, PyBuffer};
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{
+    PyBool, PyBytes, PyDate, PyDateAccess, PyDateTime, PyDelta, PyDeltaAccess, PyDict, PyFloat,
+    PyInt, PyList, PyString, PyTime, PyTimeAccess, PyTuple, PyTzInfoAccess, timezone_utc_bound,
+};
+use crate::dataframe::DataFrame;
+use crate::io;
+
+const UNIX_EPOCH_DAYS_FROM_CE: i32 = 719_163;
+
+/// The kind of Python value a column holds, which decides its Arrow type.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Kind {
+    Null,
+    Bool,
+    Int,
+    Float,
+    Str,
+    Bytes,
+    Date,
+    DateTime,
+    /// Timezone-aware datetimes, stored as UTC
+    DateTimeTz,
+    Time,
+}
+
+impl Kind {
+    fn of(value: &Bound<'_, PyAny>) -> PyResult<Kind> {
+        Ok(if value.is_none() {
+            Kind::Null
+        } else if value.is_instance_of::<PyBool>() {
+            Kind::Bool
+        } else if value.is_instance_of::<PyInt>() {
+            Kind::Int
+        } else if value.is_instance_of::<PyFloat>() {
+            Kind::Float
+        } else if value.is_instance_of::<PyString>() {
+            Kind::Str
+        } else if value.is_instance_of::<PyBytes>() {
+            Kind::Bytes
+        } else if let Ok(datetime) = value.downcast::<PyDateTime>() {
+            if datetime.get_tzinfo_bound().is_some() { Kind::DateTimeTz } else { Kind::DateTime }
+        } else if value.is_instance_of::<PyDate>() {
+            Kind::Date
+        } else if value.is_instance_of::<PyTime>() {
+            Kind::Time
+        } else {
+            return Err(PyTypeError::new_err(format!("Unsupported value type: {}", value.get_type().name()?)));
+        })
+    }
+
+    /// The kind of a column holding values of both kinds, if they can share one.
+    fn combine(self, other: Kind) -> Option<Kind> {
+        match (self, other) {
+            (a, b) if a == b => Some(a),
+            (Kind::Null, k) | (k, Kind::Null) => Some(k),
+            (Kind::Int, Kind::Float) | (Kind::Float, Kind::Int) => Some(Kind::Float),
+            _ => None,
+        }
+    }
+}
+
+/// Parses a `{column: dtype}` mapping such as `{"id": "int32", "joined": "date"}`.
+pub fn parse_dtypes(dtypes: Option<HashMap<String, String>>) -> PyResult<HashMap<String, DataType>> {
+    dtypes.unwrap_or_default().into_iter()
+        .map(|(name, dtype)| Ok((name, io::parse_dtype(&dtype).map_err(|e| PyValueError::new_err(e.to_string()))?)))
+        .collect()
+}
+
+/// Collects the values of a list, tuple, NumPy array or other iterable. Objects with
+/// `tolist()` are converted first, so NumPy scalars become Python values.
+fn collect_values<'py>(values: &Bound<'py, PyAny>) -> PyResult<Vec<Bound<'py, PyAny>>> {
+    let values = if values.hasattr("tolist")? { values.call_method0("tolist")? } else { values.clone() };
+    values.iter()?.collect()
+}
+
+/// Builds an Arrow array from Python values. Without a `dtype`, the type follows the
+/// values: bools, ints, floats (ints mixed with floats become floats), strs, bytes,
+/// dates, datetimes and times; a column of only None becomes Utf8. With a `dtype`,
+/// the inferred array is cast to it and values that don't convert raise an error.
+fn array_from_values(name: &str, values: &[Bound<'_, PyAny>], dtype: Option<&DataType>) -> PyResult<ArrayRef> {
+    let mut kind = Kind::Null;
+    for value in values {
+        let value_kind = Kind::of(value)?;
+        kind = kind.combine(value_kind).ok_or_else(|| {
+            PyTypeError::new_err(format!("Column {} mixes {:?} and {:?} values", name, kind, value_kind))
+        })?;
+    }
+
+    let array: ArrayRef = match kind {
+        Kind::Null => Arc::new(StringArray::new_null(values.len())),
+        Kind::Bool => build(values, BooleanBuilder::new(), |v| v.extract::<bool>())?,
+        Kind::Int => build(values, Int64Builder::new(), |v| v.extract::<i64>())?,
+        Kind::Float => build(values, Float64Builder::new(), |v| v.extract::<f64>())?,
+        Kind::Str => build(values, StringBuilder::new(), |v| Ok(v.downcast::<PyString>()?.to_str()?.to_string()))?,
+        Kind::Bytes => build(values, BinaryBuilder::new(), |v| Ok(v.downcast::<PyBytes>()?.as_bytes().to_vec()))?,
+        Kind::Date => build(values, Date32Builder::new(), |v| date_days(v.downcast::<PyDate>()?))?,
+        Kind::DateTime => build(values, TimestampMicrosecondBuilder::new(), |v| datetime_micros(v.downcast::<PyDateTime>()?))?,
+        Kind::DateTimeTz => {
+            let builder = TimestampMicrosecondBuilder::new().with_timezone("+00:00");
+            build(values, builder, |v| {
+                let offset = v.call_method0("utcoffset")?;
+                Ok(datetime_micros(v.downcast::<PyDateTime>()?)? - delta_micros(offset.downcast::<PyDelta>()?))
+            })?
+        }
+        Kind::Time => build(values, Time64MicrosecondBuilder::new(), |v| time_micros(v.downcast::<PyTime>()?))?,
+    };
+
+    match dtype {
+        Some(dtype) if dtype != array.data_type() => cast_column(name, &array, dtype),
+        _ => Ok(array),
+    }
+}
+
+/// A one-element array holding a Python value, for expression literals. None
+/// becomes a null of type Null, which takes on the type of what it meets.
+pub fn scalar_from_value(value: &Bound<'_, PyAny>) -> PyResult<ArrayRef> {
+    if value.is_none() {
+        return Ok(Arc::new(NullArray::new(1)));
+    }
+    array_from_values("literal", std::slice::from_ref(value), None)
+}
+
+fn cast_column(name: &str, array: &ArrayRef, dtype: &DataType) -> PyResult<ArrayRef> {
+    let strict = CastOptions { safe: false, ..Default::default() };
+    cast_with_options(array, dtype, &strict)
+        .map_err(|e| PyValueError::new_err(format!("Cannot convert column {} to {}: {}", name, dtype, e)))
+}
+
+/// Appends each value (or a null for None) to `builder` and finishes it.
+fn build<B, T>(
+    values: &[Bound<'_, PyAny>],
+    mut builder: B,
+    convert: impl Fn(&Bound<'_, PyAny>) -> PyResult<T>,
+) -> PyResult<ArrayRef>
+where
+    B: arrow_array::builder::ArrayBuilder + Extend<Option<T>>,
+{
+    let converted = values.iter()
+        .map(|v| if v.is_none() { Ok(None) } else { convert(v).map(Some) })
+        .collect::<PyResult<Vec<Option<T>>>>()?;
+    builder.extend(converted);
+    Ok(builder.finish())
+}
+
+fn date_days(date: &Bound<'_, PyDate>) -> PyResult<i32> {
+    let date = NaiveDate::from_ymd_opt(date.get_year(), date.get_month().into(), date.get_day().into())
+        .ok_or_else(|| PyValueError::new_err("Invalid date"))?;
+    Ok(date.num_days_from_ce() - UNIX_EPOCH_DAYS_FROM_CE)
+}
+
+fn datetime_micros(datetime: &Bound<'_, PyDateTime>) -> PyResult<i64> {
+    let days = date_days(datetime.downcast::<PyDate>()?)? as i64;
+    let seconds = datetime.get_hour() as i64 * 3600 + datetime.get_minute() as i64 * 60 + datetime.get_second() as i64;
+    Ok((days * 86_400 + seconds) * 1_000_000 + datetime.get_microsecond() as i64)
+}
+
+fn time_micros(time: &Bound<'_, PyTime>) -> PyResult<i64> {
+    let seconds = time.get_hour() as i64 * 3600 + time.get_minute() as i64 * 60 + time.get_second() as i64;
+    Ok(seconds * 1_000_000 + time.get_microsecond() as i64)
+}
+
+fn delta_micros(delta: &Bound<'_, PyDelta>) -> i64 {
+    (delta.get_days() as i64 * 86_400 + delta.get_seconds() as i64) * 1_000_000 + delta.get_microseconds() as i64
+}
+
+/// Assembles named columns into a single-batch DataFrame. Without columns there
+/// is no row count, so the result is empty like `DataFrame()`.
+fn dataframe_from_columns(columns: Vec<(String, ArrayRef)>) -> PyResult<DataFrame> {
+    if columns.is_empty() {
+        return Ok(DataFrame { batches: Vec::new() });
+    }
+    if let Some((name, array)) = columns.iter().find(|(_, a)| a.len() != columns[0].1.len()) {
+        return Err(PyValueError::new_err(format!(
+            "Column {} has {} values, expected {}", name, array.len(), columns[0].1.len()
+        )));
+    }
+    let (fields, arrays): (Vec<Field>, Vec<ArrayRef>) = columns.into_iter()
+        .map(|(name, array)| (Field::new(name, array.data_type().clone(), true), array))
+        .unzip();
+    let batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(DataFrame { batches: vec![batch] })
+}
+
+fn check_dtype_names(dtypes: &HashMap<String, DataType>, names: &[String]) -> PyResult<()> {
+    match dtypes.keys().find(|k| !names.contains(k)) {
+        Some(name) => Err(PyValueError::new_err(format!("schema given for unknown column: {}", name))),
+        None => Ok(()),
+    }
+}
+
+/// Builds a DataFrame from a `{column: values}` dict.
+pub fn from_dict(data: &Bound<'_, PyDict>, dtypes: &HashMap<String, DataType>) -> PyResult<DataFrame> {
+    let mut columns = Vec::with_capacity(data.len());
+    for (name, values) in data.iter() {
+        let name: String = name.extract()?;
+        let array = array_from_values(&name, &collect_values(&values)?, dtypes.get(&name))?;
+        columns.push((name, array));
+    }
+    let names: Vec<String> = columns.iter().map(|(n, _)| n.clone()).collect();
+    check_dtype_names(dtypes, &names)?;
+    dataframe_from_columns(columns)
+}
+
+/// Builds a DataFrame from a sequence of dicts. Columns appear in the order their
+/// keys are first seen, and records without a key get None.
+pub fn from_records(records: &Bound<'_, PyAny>, dtypes: &HashMap<String, DataType>) -> PyResult<DataFrame> {
+    let py = records.py();
+    let records = collect_values(records)?;
+    let mut names: Vec<String> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut values: Vec<Vec<Bound<'_, PyAny>>> = Vec::new();
+
+    for (row, record) in records.iter().enumerate() {
+        let record = record.downcast::<PyDict>()
+            .map_err(|_| PyTypeError::new_err(format!("Record {} is not a dict", row)))?;
+        for (key, value) in record.iter() {
+            let key: String = key.extract()?;
+            let column = *index.entry(key.clone()).or_insert_with(|| {
+                names.push(key);
+                values.push(vec![py.None().into_bound(py); row]);
+                values.len() - 1
+            });
+            values[column].push(value);
+        }
+        for column in values.iter_mut().filter(|c| c.len() == row) {
+            column.push(py.None().into_bound(py));
+        }
+    }
+
+    check_dtype_names(dtypes, &names)?;
+    let columns = names.into_iter().zip(values)
+        .map(|(name, values)| {
+            let array = array_from_values(&name, &values, dtypes.get(&name))?;
+            Ok((name, array))
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    dataframe_from_columns(columns)
+}
+
+/// Builds a DataFrame from a 1-D (one column) or 2-D (rows by columns) array.
+/// Numeric arrays are read through the buffer protocol; anything else, such as
+/// boolean, string or object arrays, goes through `tolist()`.
+pub fn from_numpy(
+    array: &Bound<'_, PyAny>,
+    names: Option<Vec<String>>,
+    dtypes: &HashMap<String, DataType>,
+) -> PyResult<DataFrame> {
+    let arrays = match buffer_columns(array)? {
+        Some(arrays) => arrays,
+        None => {
+            let rows = collect_values(array)?;
+            let two_dimensional = rows.first()
+                .is_some_and(|r| r.is_instance_of::<PyList>() || r.is_instance_of::<PyTuple>());
+            if two_dimensional {
+                let rows = rows.iter().map(collect_values).collect::<PyResult<Vec<_>>>()?;
+                let width = rows[0].len();
+                if let Some(row) = rows.iter().position(|r| r.len() != width) {
+                    return Err(PyValueError::new_err(format!("Row {} has {} values, expected {}", row, rows[row].len(), width)));
+                }
+                (0..width)
+                    .map(|j| {
+                        let column: Vec<_> = rows.iter().map(|r| r[j].clone()).collect();
+                        array_from_values(&format!("column_{}", j + 1), &column, None)
+                    })
+                    .collect::<PyResult<Vec<_>>>()?
+            } else {
+                vec![array_from_values("column_1", &rows, None)?]
+            }
+        }
+    };
+
+    // Structured arrays carry their own field names
+    let dtype_names: Option<Vec<String>> = match array.getattr("dtype") {
+        Ok(dtype) => dtype.getattr("names").ok().and_then(|n| n.extract().ok()),
+        Err(_) => None,
+    };
+    let names = match names.or(dtype_names) {
+        Some(names) if names.len() != arrays.len() => {
+            return Err(PyValueError::new_err(format!("Got {} column names for {} columns", names.len(), arrays.len())));
+        }
+        Some(names) => names,
+        None => (1..=arrays.len()).map(|i| format!("column_{}", i)).collect(),
+    };
+    check_dtype_names(dtypes, &names)?;
+
+    let columns = names.into_iter().zip(arrays)
+        .map(|(name, array)| {
+            let array = match dtypes.get(&name) {
+                Some(dtype) if dtype != array.data_type() => cast_column(&name, &array, dtype)?,
+                _ => array,
+            };
+            Ok((name, array))
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    dataframe_from_columns(columns)
+}
+
+/// Reads a numeric array through the buffer protocol, returning None when the
+/// object doesn't expose a buffer of a supported element type.
+fn buffer_columns(array: &Bound<'_, PyAny>) -> PyResult<Option<Vec<ArrayRef>>> {
+    macro_rules! try_primitive {
+        ($($arrow:ty),*) => {
+            $(
+                if let Some(columns) = primitive_columns::<$arrow>(array)? {
+                    return Ok(Some(columns));
+                }
+            )*
+        };
+    }
+    try_primitive!(Float64Type, Float32Type, Int64Type, Int32Type, Int16Type, Int8Type, UInt64Type, UInt32Type, UInt16Type, UInt8Type);
+
+    Ok(None)
+}
+
+fn primitive_columns<A>(array: &Bound<'_, PyAny>) -> PyResult<Option<Vec<ArrayRef>>>
+where
+    A: ArrowPrimitiveType,
+    A::Native: Element,
+{
+    let Ok(buffer) = PyBuffer::<A::Native>::get_bound(array) else {
+        return Ok(None);
+    };
+    let values = buffer.to_vec(array.py())?;
+    let columns = split_columns(&buffer, values)?;
+    Ok(Some(columns.into_iter().map(|c| Arc::new(PrimitiveArray::<A>::from_iter_values(c)) as ArrayRef).collect()))
+}
+
+/// Splits a C-ordered 1-D or 2-D buffer into its columns.
+fn split_columns<T: Element + Copy>(buffer: &PyBuffer<T>, values: Vec<T>) -> PyResult<Vec<Vec<T>>> {
+    match buffer.shape() {
+        [_] => Ok(vec![values]),
+        [_, width] => {
+            let width = *width;
+            Ok((0..width).map(|j| values.iter().skip(j).step_by(width.max(1)).copied().collect()).collect())
+        }
+        shape => Err(PyValueError::new_err(format!("Expected a 1-D or 2-D array, got {} dimensions", shape.len()))),
+    }
+}
+
+/// Converts an Arrow array to Python values: numbers, `str`, `bytes`, `datetime`
+/// objects for temporal types, `Decimal` for decimals, lists for list arrays and
+/// dicts for structs. Nulls become None.
+pub fn array_to_values(py: Python<'_>, array: &dyn Array) -> PyResult<Vec<PyObject>> {
+    let arrow_error = |e: arrow_schema::ArrowError| PyValueError::new_err(e.to_string());
+    let n = array.len();
+    let mut values = Vec::with_capacity(n);
+    macro_rules! each {
+        ($array:expr, |$v:ident| $convert:expr) => {{
+            let array = $array;
+            for i in 0..n {
+                values.push(if array.is_null(i) { py.None() } else { let $v = array.value(i); $convert });
+            }
+        }};
+    }
+
+    match array.data_type() {
+        DataType::Null => values.extend((0..n).map(|_| py.None())),
+        DataType::Boolean => each!(array.as_boolean(), |v| v.into_py(py)),
+        DataType::Int8 => each!(array.as_primitive::<Int8Type>(), |v| v.into_py(py)),
+        DataType::Int16 => each!(array.as_primitive::<Int16Type>(), |v| v.into_py(py)),
+        DataType::Int32 => each!(array.as_primitive::<Int32Type>(), |v| v.into_py(py)),
+        DataType::Int64 => each!(array.as_primitive::<Int64Type>(), |v| v.into_py(py)),
+        DataType::UInt8 => each!(array.as_primitive::<UInt8Type>(), |v| v.into_py(py)),
+        DataType::UInt16 => each!(array.as_primitive::<UInt16Type>(), |v| v.into_py(py)),
+        DataType::UInt32 => each!(array.as_primitive::<UInt32Type>(), |v| v.into_py(py)),
+        DataType::UInt64 => each!(array.as_primitive::<UInt64Type>(), |v| v.into_py(py)),
+        DataType::Float16 => each!(array.as_primitive::<Float16Type>(), |v| v.to_f64().into_py(py)),
+        DataType::Float32 => each!(array.as_primitive::<Float32Type>(), |v| v.into_py(py)),
+        DataType::Float64 => each!(array.as_primitive::<Float64Type>(), |v| v.into_py(py)),
+        DataType::Utf8 => each!(array.as_string::<i32>(), |v| v.into_py(py)),
+        DataType::LargeUtf8 => each!(array.as_string::<i64>(), |v| v.into_py(py)),
+        DataType::Binary => each!(array.as_binary::<i32>(), |v| PyBytes::new_bound(py, v).into_py(py)),
+        DataType::LargeBinary => each!(array.as_binary::<i64>(), |v| PyBytes::new_bound(py, v).into_py(py)),
+        DataType::FixedSizeBinary(_) => each!(array.as_fixed_size_binary(), |v| PyBytes::new_bound(py, v).into_py(py)),
+        DataType::Date32 => {
+            let array = array.as_primitive::<Date32Type>();
+            for i in 0..n {
+                values.push(match array.value_as_date(i).filter(|_| array.is_valid(i)) {
+                    Some(date) => PyDate::new_bound(py, date.year(), date.month() as u8, date.day() as u8)?.into_py(py),
+                    None => py.None(),
+                });
+            }
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, tz) => {
+            let array = array.as_primitive::<TimestampMicrosecondType>();
+            // Aware timestamps are built in UTC and then moved to the column's timezone
+            let target = match tz.as_deref() {
+                None => None,
+                Some("UTC" | "Z" | "+00:00") => Some(None),
+                Some(tz) => Some(Some(python_timezone(py, tz)?)),
+            };
+            let utc = timezone_utc_bound(py);
+            for i in 0..n {
+                let Some(datetime) = array.value_as_datetime(i).filter(|_| array.is_valid(i)) else {
+                    values.push(py.None());
+                    continue;
+                };
+                let tzinfo = target.as_ref().map(|_| &utc);
+                let value = PyDateTime::new_bound(
+                    py, datetime.year(), datetime.month() as u8, datetime.day() as u8,
+                    datetime.hour() as u8, datetime.minute() as u8, datetime.second() as u8,
+                    datetime.nanosecond() / 1000 % 1_000_000, tzinfo,
+                )?;
+                values.push(match &target {
+                    Some(Some(zone)) => value.call_method1("astimezone", (zone,))?.unbind(),
+                    _ => value.into_py(py),
+                });
+            }
+        }
+        DataType::Time64(TimeUnit::Microsecond) => {
+            let array = array.as_primitive::<Time64MicrosecondType>();
+            for i in 0..n {
+                values.push(match array.value_as_time(i).filter(|_| array.is_valid(i)) {
+                    Some(time) => PyTime::new_bound(
+                        py, time.hour() as u8, time.minute() as u8, time.second() as u8,
+                        time.nanosecond() / 1000 % 1_000_000, None,
+                    )?.into_py(py),
+                    None => py.None(),
+                });
+            }
+        }
+        DataType::Duration(TimeUnit::Microsecond) => {
+            let array = array.as_primitive::<DurationMicrosecondType>();
+            for i in 0..n {
+                values.push(if array.is_null(i) {
+                    py.None()
+                } else {
+                    let micros = array.value(i);
+                    let (days, rest) = (micros.div_euclid(86_400_000_000), micros.rem_euclid(86_400_000_000));
+                    let days = i32::try_from(days).map_err(|_| PyValueError::new_err("Duration out of range"))?;
+                    PyDelta::new_bound(py, days, (rest / 1_000_000) as i32, (rest % 1_000_000) as i32, false)?.into_py(py)
+                });
+            }
+        }
+        // Normalize the other temporal units to the ones handled above
+        DataType::Date64 => return array_to_values(py, &cast(array, &DataType::Date32).map_err(arrow_error)?),
+        DataType::Timestamp(_, tz) => {
+            let micros = cast(array, &DataType::Timestamp(TimeUnit::Microsecond, tz.clone())).map_err(arrow_error)?;
+            return array_to_values(py, &micros);
+        }
+        DataType::Time32(_) | DataType::Time64(_) => {
+            return array_to_values(py, &cast(array, &DataType::Time64(TimeUnit::Microsecond)).map_err(arrow_error)?);
+        }
+        DataType::Duration(_) => {
+            return array_to_values(py, &cast(array, &DataType::Duration(TimeUnit::Microsecond)).map_err(arrow_error)?);
+        }
+        DataType::Decimal128(_, _) | DataType::Decimal256(_, _) => {
+            let decimal = py.import_bound("decimal")?.getattr("Decimal")?;
+            let text = cast(array, &DataType::Utf8).map_err(arrow_error)?;
+            each!(text.as_string::<i32>(), |v| decimal.call1((v,))?.unbind());
+        }
+        DataType::Dictionary(_, value_type) => {
+            return array_to_values(py, &cast(array, value_type).map_err(arrow_error)?);
+        }
+        DataType::List(_) => each!(array.as_list::<i32>(), |v| PyList::new_bound(py, array_to_values(py, &v)?).into_py(py)),
+        DataType::LargeList(_) => each!(array.as_list::<i64>(), |v| PyList::new_bound(py, array_to_values(py, &v)?).into_py(py)),
+        DataType::Struct(fields) => {
+            let array = array.as_struct();
+            let children = array.columns().iter()
+                .map(|c| array_to_values(py, c))
+                .collect::<PyResult<Vec<_>>>()?;
+            for i in 0..n {
+                if array.is_null(i) {
+                    values.push(py.None());
+                    continue;
+                }
+                let dict = PyDict::new_bound(py);
+                for (field, child) in fields.iter().zip(&children) {
+                    dict.set_item(field.name(), &child[i])?;
+                }
+                values.push(dict.into_py(py));
+            }
+        }
+        other => {
+            let text = cast(array, &DataType::Utf8)
+                .map_err(|_| PyTypeError::new_err(format!("Cannot convert {} values to Python", other)))?;
+            return array_to_values(py, &text);
+        }
+    }
+    Ok(values)
+}
+
+/// Looks up a timezone given as a fixed offset such as `"+02:00"` or an IANA name.
+fn python_timezone<'py>(py: Python<'py>, tz: &str) -> PyResult<Bound<'py, PyAny>> {
+    let datetime = py.import_bound("datetime")?;
+    let offset = tz.strip_prefix('+').map(|o| (1, o)).or_else(|| tz.strip_prefix('-').map(|o| (-1, o)));
+    if let Some((sign, offset)) = offset {
+        let (hours, minutes) = offset.split_once(':').unwrap_or((offset, "0"));
+        if let (Ok(hours), Ok(minutes)) = (hours.parse::<i32>(), minutes.parse::<i32>()) {
+            let delta = PyDelta::new_bound(py, 0, sign * (hours * 3600 + minutes * 60), 0, true)?;
+            return datetime.getattr("timezone")?.call1((delta,));
+        }
+    }
+    py.import_bound("zoneinfo")?.getattr("ZoneInfo")?.call1((tz,))
+}
+
+/// Each column's name and Python values, across all batches.
+pub fn dataframe_to_columns(py: Python<'_>, df: &DataFrame) -> PyResult<Vec<(String, Vec<PyObject>)>> {
+    let schema = df.schema();
+    schema.fields().iter().enumerate()
+        .map(|(i, field)| {
+            let mut values = Vec::with_capacity(df.row_count());
+            for batch in &df.batches {
+                values.extend(array_to_values(py, batch.column(i))?);
+            }
+            Ok((field.name().clone(), values))
+        })
+        .collect()
+}
+
+/// Copies the numeric and boolean columns into a 2-D NumPy array of shape
+/// (rows, columns). Booleans alone give a bool array, integers (and booleans) an
+/// int64 array, and anything else or any null a float64 array with NaN for nulls.
+pub fn dataframe_to_numpy<'py>(py: Python<'py>, df: &DataFrame) -> PyResult<Bound<'py, PyAny>> {
+    let schema = df.schema();
+    if let Some(field) = schema.fields().iter().find(|f| !(f.data_type().is_numeric() || f.data_type() == &DataType::Boolean)) {
+        return Err(PyValueError::new_err(format!(
+            "to_numpy supports numeric and boolean columns only; column {} is {}", field.name(), field.data_type()
+        )));
+    }
+    let has_nulls = df.batches.iter().any(|b| b.columns().iter().any(|c| c.null_count() > 0));
+    let all_bool = schema.fields().iter().all(|f| f.data_type() == &DataType::Boolean);
+    let all_int = schema.fields().iter().all(|f| f.data_type().is_integer() || f.data_type() == &DataType::Boolean);
+    let (rows, width) = (df.row_count(), schema.fields().len());
+
+    let numpy = py.import_bound("numpy")?;
+    let columns: Vec<ArrayRef> = (0..width)
+        .map(|i| {
+            let chunks: Vec<&dyn Array> = df.batches.iter().map(|b| b.column(i).as_ref()).collect();
+            arrow_select::concat::concat(&chunks).map_err(|e| PyValueError::new_err(e.to_string()))
+        })
+        .collect::<PyResult<_>>()?;
+
+    if all_bool && !has_nulls {
+        let out = numpy.call_method1("zeros", ((rows, width), "uint8"))?;
+        let buffer = PyBuffer::<u8>::get_bound(&out)?;
+        let data: Vec<u8> = (0..rows)
+            .flat_map(|r| columns.iter().map(move |c| c.as_boolean().value(r) as u8))
+            .collect();
+        buffer.copy_from_slice(py, &data)?;
+        return out.call_method1("view", ("bool",));
+    }
+    if all_int && !has_nulls {
+        let columns = columns.iter()
+            .map(|c| cast_column("", c, &DataType::Int64))
+            .collect::<PyResult<Vec<_>>>()?;
+        let out = numpy.call_method1("zeros", ((rows, width), "int64"))?;
+        let buffer = PyBuffer::<i64>::get_bound(&out)?;
+        let data: Vec<i64> = (0..rows)
+            .flat_map(|r| columns.iter().map(move |c| c.as_primitive::<Int64Type>().value(r)))
+            .collect();
+        buffer.copy_from_slice(py, &data)?;
+        return Ok(out);
+    }
+    let columns = columns.iter()
+        .map(|c| cast(c, &DataType::Float64).map_err(|e| PyValueError::new_err(e.to_string())))
+        .collect::<PyResult<Vec<_>>>()?;
+    let out = numpy.call_method1("zeros", ((rows, width), "float64"))?;
+    let buffer = PyBuffer::<f64>::get_bound(&out)?;
+    let data: Vec<f64> = (0..rows)
+        .flat_map(|r| columns.iter().map(move |c| {
+            let c = c.as_primitive::<Float64Type>();
+            if c.is_null(r) { f64::NAN } else { c.value(r) }
+        }))
+        .collect();
+    buffer.copy_from_slice(py, &data)?;
+    Ok(out)
+}
@@ -0,0 +1,450 @@
+use std::sync::Arc;
+use arrow_array::{
+    ArrayRef, BooleanArray, Date32Array, Float64Array, Int64Array, NullArray, StringArray,
+    Time64MicrosecondArray, TimestampMicrosecondArray,
+};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use crate::expr::{BinaryOp, Node};
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    /// A column name in backticks
+    QuotedIdent(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Comma,
+}
+
+/// Longest first, so that `!~*` isn't read as `!` followed by `~*`
+const OPERATORS: [&str; 18] = [
+    "!~*", "==", "!=", "<>", "<=", ">=", "~*", "!~", "<", ">", "=", "+", "-", "*", "/", "%", "!", "~",
+];
+
+/// Splits a query into tokens, each with the character position it starts at.
+fn tokenize(text: &str) -> PyResult<Vec<(Token, usize)>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let token = if c == '(' {
+            i += 1;
+            Token::LParen
+        } else if c == ')' {
+            i += 1;
+            Token::RParen
+        } else if c == ',' {
+            i += 1;
+            Token::Comma
+        } else if c == '\'' || c == '"' {
+            let (text, end) = quoted(&chars, start).ok_or_else(|| syntax_error(start, "unterminated string"))?;
+            i = end;
+            Token::Str(text)
+        } else if c == '`' {
+            let (name, end) = quoted(&chars, start).ok_or_else(|| syntax_error(start, "unterminated `"))?;
+            i = end;
+            Token::QuotedIdent(name)
+        } else if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit())) {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.'
+                || (matches!(chars[i], '+' | '-') && matches!(chars[i - 1], 'e' | 'E'))) {
+                i += 1;
+            }
+            let number: String = chars[start..i].iter().collect();
+            match number.parse::<i64>() {
+                Ok(n) => Token::Int(n),
+                Err(_) => Token::Float(number.parse().map_err(|_| syntax_error(start, &format!("invalid number {}", number)))?),
+            }
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            Token::Ident(chars[start..i].iter().collect())
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 3)].iter().collect();
+            let op = OPERATORS.iter().find(|op| rest.starts_with(*op))
+                .ok_or_else(|| syntax_error(start, &format!("unexpected character {:?}", c)))?;
+            i += op.len();
+            Token::Op(op)
+        };
+        tokens.push((token, start));
+    }
+    Ok(tokens)
+}
+
+/// Reads the text between the quote at `start` and its closing quote, returning it
+/// with the position after the closing quote. A doubled quote or a backslash
+/// escapes the quote character; a backslash also escapes itself.
+fn quoted(chars: &[char], start: usize) -> Option<(String, usize)> {
+    let quote = chars[start];
+    let mut text = String::new();
+    let mut i = start + 1;
+    loop {
+        match *chars.get(i)? {
+            '\\' if matches!(chars.get(i + 1), Some(&c) if c == quote || c == '\\') => {
+                text.push(chars[i + 1]);
+                i += 2;
+            }
+            c if c == quote && chars.get(i + 1) == Some(&quote) => {
+                text.push(quote);
+                i += 2;
+            }
+            c if c == quote => return Some((text, i + 1)),
+            c => {
+                text.push(c);
+                i += 1;
+            }
+        }
+    }
+}
+
+/// Keywords for the string operators, each placed between a column and a pattern.
+const STRING_OPERATORS: [&str; 5] = ["like", "ilike", "startswith", "endswith", "contains"];
+
+fn syntax_error(position: usize, message: &str) -> PyErr {
+    PyValueError::new_err(format!("Invalid query at position {}: {}", position, message))
+}
+
+/// Parses a filter expression such as
+/// `(price > 10 and region in ('EU', 'US')) or name is null` into an expression tree.
+///
+/// A bare word on the right of a comparison that isn't one of `columns` is read as
+/// a string, so `name == Alice` keeps working.
+pub fn parse(text: &str, columns: &[String]) -> PyResult<Node> {
+    let mut parser = Parser { tokens: tokenize(text)?, pos: 0, end: text.chars().count(), columns };
+    let node = parser.or()?;
+    match parser.tokens.get(parser.pos) {
+        None => Ok(node),
+        Some((token, at)) => Err(syntax_error(*at, &format!("unexpected {}", describe(token)))),
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    /// Length of the query, reported as the position of errors at the end
+    end: usize,
+    columns: &'a [String],
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.pos).map(|(_, at)| *at).unwrap_or(self.end)
+    }
+
+    fn error(&self, expected: &str) -> PyErr {
+        let found = self.peek().map(describe).unwrap_or_else(|| "end of query".to_string());
+        syntax_error(self.position(), &format!("expected {}, found {}", expected, found))
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek().cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn at_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found = self.at_keyword(keyword);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn symbol(&mut self, op: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Op(o)) if *o == op);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, token: Token, expected: &str) -> PyResult<()> {
+        if self.peek() == Some(&token) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(expected))
+        }
+    }
+
+    fn or(&mut self) -> PyResult<Node> {
+        let mut node = self.and()?;
+        while self.keyword("or") {
+            node = Node::binary(BinaryOp::Or, node, self.and()?);
+        }
+        Ok(node)
+    }
+
+    fn and(&mut self) -> PyResult<Node> {
+        let mut node = self.not()?;
+        while self.keyword("and") {
+            node = Node::binary(BinaryOp::And, node, self.not()?);
+        }
+        Ok(node)
+    }
+
+    fn not(&mut self) -> PyResult<Node> {
+        if self.keyword("not") || self.symbol("!") {
+            return Ok(Node::Not(Box::new(self.not()?)));
+        }
+        self.predicate()
+    }
+
+    fn predicate(&mut self) -> PyResult<Node> {
+        let left = self.additive()?;
+        if self.keyword("is") {
+            let negated = self.keyword("not");
+            if !self.keyword("null") {
+                return Err(self.error("null"));
+            }
+            return Ok(if negated { Node::IsNotNull(Box::new(left)) } else { Node::IsNull(Box::new(left)) });
+        }
+
+        let negated = self.at_keyword("not") && matches!(
+            self.tokens.get(self.pos + 1),
+            Some((Token::Ident(w), _)) if ["in", "between"].iter().chain(&STRING_OPERATORS).any(|k| w.eq_ignore_ascii_case(k))
+        );
+        if negated {
+            self.pos += 1;
+        }
+        let node = if self.keyword("in") {
+            self.expect(Token::LParen, "(")?;
+            let mut values = vec![self.value()?];
+            while self.peek() == Some(&Token::Comma) {
+                self.pos += 1;
+                values.push(self.value()?);
+            }
+            self.expect(Token::RParen, ")")?;
+            Node::is_in(left, values)
+        } else if self.keyword("between") {
+            let lower = self.value()?;
+            if !self.keyword("and") {
+                return Err(self.error("and"));
+            }
+            Node::between(left, lower, self.value()?)
+        } else if let Some(op) = self.string_operator() {
+            let right = self.value()?;
+            Node::binary(op, left, right)
+        } else if let Some((op, negate)) = self.regex_operator() {
+            let node = Node::binary(op, left, self.value()?);
+            if negate { Node::Not(Box::new(node)) } else { node }
+        } else if let Some(op) = self.comparison() {
+            let right = self.value()?;
+            Node::binary(op, left, right)
+        } else {
+            return Ok(left);
+        };
+        Ok(if negated { Node::Not(Box::new(node)) } else { node })
+    }
+
+    fn string_operator(&mut self) -> Option<BinaryOp> {
+        let op = match self.peek()? {
+            Token::Ident(word) => match word.to_ascii_lowercase().as_str() {
+                "like" => BinaryOp::Like,
+                "ilike" => BinaryOp::ILike,
+                "startswith" => BinaryOp::StartsWith,
+                "endswith" => BinaryOp::EndsWith,
+                "contains" => BinaryOp::Contains,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        self.pos += 1;
+        Some(op)
+    }
+
+    /// `~` and `~*` match a regular expression, case-sensitively or not; `!~` and
+    /// `!~*` are their negations.
+    fn regex_operator(&mut self) -> Option<(BinaryOp, bool)> {
+        let op = match self.peek()? {
+            Token::Op("~") => (BinaryOp::Matches, false),
+            Token::Op("~*") => (BinaryOp::IMatches, false),
+            Token::Op("!~") => (BinaryOp::Matches, true),
+            Token::Op("!~*") => (BinaryOp::IMatches, true),
+            _ => return None,
+        };
+        self.pos += 1;
+        Some(op)
+    }
+
+    fn comparison(&mut self) -> Option<BinaryOp> {
+        let op = match self.peek()? {
+            Token::Op("==" | "=") => BinaryOp::Eq,
+            Token::Op("!=" | "<>") => BinaryOp::NotEq,
+            Token::Op("<") => BinaryOp::Lt,
+            Token::Op("<=") => BinaryOp::LtEq,
+            Token::Op(">") => BinaryOp::Gt,
+            Token::Op(">=") => BinaryOp::GtEq,
+            _ => return None,
+        };
+        self.pos += 1;
+        Some(op)
+    }
+
+    /// An operand compared against, where an unknown bare word is a string.
+    fn value(&mut self) -> PyResult<Node> {
+        let bare = matches!(self.peek(), Some(Token::Ident(_)));
+        match self.additive()? {
+            Node::Column(name) if bare && !self.columns.contains(&name) => Ok(string(name)),
+            node => Ok(node),
+        }
+    }
+
+    fn additive(&mut self) -> PyResult<Node> {
+        let mut node = self.multiplicative()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Op("+")) => BinaryOp::Add,
+                Some(Token::Op("-")) => BinaryOp::Sub,
+                _ => return Ok(node),
+            };
+            self.pos += 1;
+            node = Node::binary(op, node, self.multiplicative()?);
+        }
+    }
+
+    fn multiplicative(&mut self) -> PyResult<Node> {
+        let mut node = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Op("*")) => BinaryOp::Mul,
+                Some(Token::Op("/")) => BinaryOp::Div,
+                Some(Token::Op("%")) => BinaryOp::Rem,
+                _ => return Ok(node),
+            };
+            self.pos += 1;
+            node = Node::binary(op, node, self.unary()?);
+        }
+    }
+
+    fn unary(&mut self) -> PyResult<Node> {
+        if self.peek() == Some(&Token::Op("-")) {
+            self.pos += 1;
+            return Ok(match self.peek() {
+                Some(&Token::Int(n)) => { self.pos += 1; int(-n) }
+                Some(&Token::Float(f)) => { self.pos += 1; float(-f) }
+                _ => Node::Negate(Box::new(self.unary()?)),
+            });
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> PyResult<Node> {
+        let position = self.position();
+        match self.next() {
+            Some(Token::Int(n)) => Ok(int(n)),
+            Some(Token::Float(f)) => Ok(float(f)),
+            Some(Token::Str(s)) => Ok(string(s)),
+            Some(Token::LParen) => {
+                let node = self.or()?;
+                self.expect(Token::RParen, ")")?;
+                Ok(node)
+            }
+            Some(Token::QuotedIdent(name)) => Ok(Node::Column(name)),
+            Some(Token::Ident(word)) => {
+                let keyword = word.to_ascii_lowercase();
+                if let (Some(Token::Str(text)), "date" | "time" | "timestamp") = (self.peek(), keyword.as_str()) {
+                    let text = text.clone();
+                    self.pos += 1;
+                    return temporal(&keyword, &text)
+                        .ok_or_else(|| syntax_error(position, &format!("invalid {} literal '{}'", keyword, text)));
+                }
+                Ok(match keyword.as_str() {
+                    "true" => literal(Arc::new(BooleanArray::from(vec![true])), "True"),
+                    "false" => literal(Arc::new(BooleanArray::from(vec![false])), "False"),
+                    "null" => literal(Arc::new(NullArray::new(1)), "None"),
+                    _ => Node::Column(word),
+                })
+            }
+            _ => {
+                self.pos -= 1;
+                Err(syntax_error(position, &format!(
+                    "expected a value or column, found {}",
+                    self.peek().map(describe).unwrap_or_else(|| "end of query".to_string())
+                )))
+            }
+        }
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Ident(word) => word.clone(),
+        Token::Int(n) => n.to_string(),
+        Token::Float(f) => f.to_string(),
+        Token::Str(s) => format!("'{}'", s),
+        Token::QuotedIdent(name) => format!("`{}`", name),
+        Token::Op(op) => op.to_string(),
+        Token::LParen => "(".to_string(),
+        Token::RParen => ")".to_string(),
+        Token::Comma => ",".to_string(),
+    }
+}
+
+fn literal(array: ArrayRef, repr: &str) -> Node {
+    Node::Literal(array, repr.to_string())
+}
+
+fn int(n: i64) -> Node {
+    literal(Arc::new(Int64Array::from(vec![n])), &n.to_string())
+}
+
+fn float(f: f64) -> Node {
+    literal(Arc::new(Float64Array::from(vec![f])), &format!("{:?}", f))
+}
+
+fn string(s: String) -> Node {
+    let repr = format!("{:?}", s);
+    literal(Arc::new(StringArray::from(vec![s])), &repr)
+}
+
+/// A `date '2024-01-15'`, `time '13:45:00'` or `timestamp '2024-01-15 08:30:00'`
+/// literal. Timestamps with an offset or `Z` are converted to UTC.
+fn temporal(keyword: &str, text: &str) -> Option<Node> {
+    let repr = format!("{} '{}'", keyword, text);
+    let array: ArrayRef = match keyword {
+        "date" => {
+            let date = NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()?;
+            Arc::new(Date32Array::from(vec![(date - DateTime::UNIX_EPOCH.date_naive()).num_days() as i32]))
+        }
+        "time" => {
+            let time = NaiveTime::parse_from_str(text, "%H:%M:%S%.f")
+                .or_else(|_| NaiveTime::parse_from_str(text, "%H:%M")).ok()?;
+            let micros = (time - NaiveTime::MIN).num_microseconds()?;
+            Arc::new(Time64MicrosecondArray::from(vec![micros]))
+        }
+        _ => {
+            let text = text.replacen(' ', "T", 1);
+            if let Ok(datetime) = DateTime::parse_from_rfc3339(&text) {
+                let micros = datetime.timestamp_micros();
+                Arc::new(TimestampMicrosecondArray::from(vec![micros]).with_timezone("+00:00"))
+            } else {
+                let datetime = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M"].iter()
+                    .find_map(|format| NaiveDateTime::parse_from_str(&text, format).ok())
+                    .or_else(|| NaiveDate::parse_from_str(&text, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0))?;
+                Arc::new(TimestampMicrosecondArray::from(vec![datetime.and_utc().timestamp_micros()]))
+            }
+        }
+    };
+    Some(literal(array, &repr))
+}
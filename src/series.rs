@@ -0,0 +1,142 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use arrow_arith::aggregate::{sum_checked, sum};
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float64Type, Int64Type, UInt64Type};
+use arrow_array::{Array, ArrayRef};
+use arrow_cast::cast;
+use arrow_ord::sort::{sort_to_indices, SortOptions};
+use arrow_schema::{ArrowError, DataType};
+use arrow_select::concat::concat;
+use arrow_select::take::take;
+
+/// One column of a DataFrame: a name and the column's arrays, one per record batch.
+#[pyclass]
+#[derive(Clone)]
+pub struct Series {
+    pub name: String,
+    pub data_type: DataType,
+    pub chunks: Vec<ArrayRef>,
+}
+
+#[pymethods]
+impl Series {
+    #[getter]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The dtype name, as accepted by the `dtypes`/`schema` arguments where one exists.
+    #[getter]
+    pub fn dtype(&self) -> String {
+        crate::io::dtype_name(&self.data_type)
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.chunks.iter().map(|c| c.len()).sum()
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!("<grizzly.Series '{}' ({} values, {})>", self.name, self.__len__(), self.dtype())
+    }
+
+    pub fn to_list(&self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+        let mut values = Vec::with_capacity(self.__len__());
+        for chunk in &self.chunks {
+            values.extend(crate::pyvalues::array_to_values(py, chunk)?);
+        }
+        Ok(values)
+    }
+
+    pub fn null_count(&self) -> usize {
+        self.chunks.iter().map(|c| c.null_count()).sum()
+    }
+
+    /// Number of non-null values.
+    pub fn count(&self) -> usize {
+        self.__len__() - self.null_count()
+    }
+
+    /// Sum of the non-null values: an int for integer and boolean columns, a float
+    /// for floating-point columns. 0 when there are no values.
+    pub fn sum(&self, py: Python<'_>) -> PyResult<PyObject> {
+        match self.numeric_kind()? {
+            DataType::Int64 => {
+                let mut total: i64 = 0;
+                for chunk in self.cast_chunks(&DataType::Int64)? {
+                    let chunk_sum = sum_checked(chunk.as_primitive::<Int64Type>()).map_err(arrow_error)?;
+                    total = total.checked_add(chunk_sum.unwrap_or(0))
+                        .ok_or_else(|| PyValueError::new_err(format!("Sum of {} overflows int64", self.name)))?;
+                }
+                Ok(total.into_py(py))
+            }
+            DataType::UInt64 => {
+                let mut total: u64 = 0;
+                for chunk in self.cast_chunks(&DataType::UInt64)? {
+                    let chunk_sum = sum_checked(chunk.as_primitive::<UInt64Type>()).map_err(arrow_error)?;
+                    total = total.checked_add(chunk_sum.unwrap_or(0))
+                        .ok_or_else(|| PyValueError::new_err(format!("Sum of {} overflows uint64", self.name)))?;
+                }
+                Ok(total.into_py(py))
+            }
+            _ => Ok(self.float_sum()?.into_py(py)),
+        }
+    }
+
+    /// Mean of the non-null values, or None when there are none.
+    pub fn mean(&self) -> PyResult<Option<f64>> {
+        self.numeric_kind()?;
+        let count = self.count();
+        Ok((count > 0).then(|| self.float_sum()).transpose()?.map(|total| total / count as f64))
+    }
+
+    /// Smallest non-null value, or None when there are none. Works for any
+    /// orderable column, including strings and dates.
+    pub fn min(&self, py: Python<'_>) -> PyResult<PyObject> {
+        self.extreme(py, false)
+    }
+
+    /// Largest non-null value, or None when there are none.
+    pub fn max(&self, py: Python<'_>) -> PyResult<PyObject> {
+        self.extreme(py, true)
+    }
+}
+
+impl Series {
+    /// The type reductions are computed in: Int64 for signed integers and booleans,
+    /// UInt64 for unsigned integers and Float64 for floats.
+    fn numeric_kind(&self) -> PyResult<DataType> {
+        match &self.data_type {
+            DataType::Boolean | DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::Int64 => Ok(DataType::Int64),
+            DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64 => Ok(DataType::UInt64),
+            dt if dt.is_floating() || matches!(dt, DataType::Decimal128(_, _) | DataType::Decimal256(_, _)) => Ok(DataType::Float64),
+            dt => Err(PyTypeError::new_err(format!("Column {} is not numeric: {}", self.name, dt))),
+        }
+    }
+
+    fn cast_chunks(&self, dtype: &DataType) -> PyResult<Vec<ArrayRef>> {
+        self.chunks.iter().map(|c| cast(c, dtype).map_err(arrow_error)).collect()
+    }
+
+    fn float_sum(&self) -> PyResult<f64> {
+        Ok(self.cast_chunks(&DataType::Float64)?.iter()
+            .map(|c| sum(c.as_primitive::<Float64Type>()).unwrap_or(0.0))
+            .sum())
+    }
+
+    fn extreme(&self, py: Python<'_>, largest: bool) -> PyResult<PyObject> {
+        if self.count() == 0 {
+            return Ok(py.None());
+        }
+        let chunks: Vec<&dyn Array> = self.chunks.iter().map(|c| c.as_ref()).collect();
+        let values = concat(&chunks).map_err(arrow_error)?;
+        let options = SortOptions { descending: largest, nulls_first: false };
+        let index = sort_to_indices(&values, Some(options), Some(1)).map_err(arrow_error)?;
+        let value = take(&values, &index, None).map_err(arrow_error)?;
+        Ok(crate::pyvalues::array_to_values(py, &value)?.remove(0))
+    }
+}
+
+fn arrow_error(e: ArrowError) -> PyErr {
+    PyValueError::new_err(format!("{}", e))
+}
@@ -0,0 +1,249 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Cursor, Read, Seek, SeekFrom, Write};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use anyhow::{Context, Result};
+use bytes::Bytes;
+use memmap2::Mmap;
+use pyo3::buffer::PyBuffer;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyString};
+use zip::ZipArchive;
+
+/// Where a reader gets its input: a file on disk, or bytes handed over from Python.
+pub enum Source {
+    Path(String),
+    Bytes(Bytes),
+}
+
+impl Source {
+    /// How the input is referred to in error messages.
+    pub fn name(&self) -> &str {
+        match self {
+            Source::Path(path) => path,
+            Source::Bytes(_) => "<bytes>",
+        }
+    }
+
+    /// The whole input as one byte slice. Files are memory-mapped.
+    pub fn map(&self) -> io::Result<SourceData<'_>> {
+        match self {
+            Source::Path(path) => Ok(SourceData::Mapped(unsafe { Mmap::map(&File::open(path)?)? })),
+            Source::Bytes(bytes) => Ok(SourceData::Borrowed(bytes)),
+        }
+    }
+
+    /// A buffered, seekable reader positioned at the start of the input.
+    pub fn open(&self) -> io::Result<SourceReader> {
+        match self {
+            Source::Path(path) => Ok(SourceReader::File(BufReader::new(File::open(path)?))),
+            Source::Bytes(bytes) => Ok(SourceReader::Bytes(Cursor::new(bytes.clone()))),
+        }
+    }
+
+    fn zip_archive(&self) -> Result<ZipArchive<SourceReader>> {
+        let reader = self.open().with_context(|| format!("Failed to open zip archive: {}", self.name()))?;
+        ZipArchive::new(reader).with_context(|| format!("Failed to read zip archive: {}", self.name()))
+    }
+
+    /// Names of the files in a zip archive, in archive order. Directories are left out.
+    pub fn zip_members(&self) -> Result<Vec<String>> {
+        let mut archive = self.zip_archive()?;
+        let mut names = Vec::with_capacity(archive.len());
+        for i in 0..archive.len() {
+            let entry = archive.by_index_raw(i)?;
+            if !entry.is_dir() {
+                names.push(entry.name().to_string());
+            }
+        }
+        Ok(names)
+    }
+
+    /// Decompresses one member of a zip archive into memory.
+    pub fn zip_member(&self, member: &str) -> Result<Source> {
+        let mut archive = self.zip_archive()?;
+        let mut entry = archive.by_name(member)
+            .with_context(|| format!("No member named {} in zip archive: {}", member, self.name()))?;
+        let mut data = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut data)
+            .with_context(|| format!("Failed to extract {} from zip archive: {}", member, self.name()))?;
+        Ok(Source::Bytes(Bytes::from(data)))
+    }
+
+    /// A path of the form `archive.zip!member` that doesn't exist as a file names
+    /// a member inside the archive.
+    fn from_path(path: String) -> Result<Source> {
+        if !Path::new(&path).exists() {
+            if let Some(i) = path.to_ascii_lowercase().find(".zip!") {
+                let (archive, member) = (&path[..i + 4], &path[i + 5..]);
+                if Path::new(archive).is_file() {
+                    return Source::Path(archive.to_string()).zip_member(member);
+                }
+            }
+        }
+        Ok(Source::Path(path))
+    }
+}
+
+pub enum SourceData<'a> {
+    Mapped(Mmap),
+    Borrowed(&'a [u8]),
+}
+
+impl Deref for SourceData<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            SourceData::Mapped(mmap) => mmap,
+            SourceData::Borrowed(bytes) => bytes,
+        }
+    }
+}
+
+pub enum SourceReader {
+    File(BufReader<File>),
+    Bytes(Cursor<Bytes>),
+}
+
+impl Read for SourceReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            SourceReader::File(reader) => reader.read(buf),
+            SourceReader::Bytes(reader) => reader.read(buf),
+        }
+    }
+}
+
+impl BufRead for SourceReader {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        match self {
+            SourceReader::File(reader) => reader.fill_buf(),
+            SourceReader::Bytes(reader) => reader.fill_buf(),
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        match self {
+            SourceReader::File(reader) => reader.consume(amt),
+            SourceReader::Bytes(reader) => reader.consume(amt),
+        }
+    }
+}
+
+impl Seek for SourceReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            SourceReader::File(reader) => reader.seek(pos),
+            SourceReader::Bytes(reader) => reader.seek(pos),
+        }
+    }
+}
+
+/// Accepts a path (`str` or `os.PathLike`, optionally `archive.zip!member`), `bytes`
+/// or any other object supporting the buffer protocol, or a file-like object with a
+/// `read()` method, which is read to the end.
+impl<'py> FromPyObject<'py> for Source {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(bytes) = ob.downcast::<PyBytes>() {
+            return Ok(Source::Bytes(Bytes::copy_from_slice(bytes.as_bytes())));
+        }
+        if ob.is_instance_of::<PyString>() {
+            return Source::from_path(ob.extract()?).map_err(io_error);
+        }
+        if ob.hasattr("read")? {
+            let data = ob.call_method0("read")?;
+            // Text-mode streams return str
+            if let Ok(text) = data.downcast::<PyString>() {
+                return Ok(Source::Bytes(Bytes::copy_from_slice(text.to_str()?.as_bytes())));
+            }
+            return Source::extract_bound(&data);
+        }
+        if let Ok(buffer) = PyBuffer::<u8>::get_bound(ob) {
+            return Ok(Source::Bytes(Bytes::from(buffer.to_vec(ob.py())?)));
+        }
+        let path: PathBuf = ob.extract().map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                "Expected a path, bytes, a buffer or a file-like object with read()",
+            )
+        })?;
+        Source::from_path(path.to_string_lossy().into_owned()).map_err(io_error)
+    }
+}
+
+fn io_error(e: anyhow::Error) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e))
+}
+
+/// Where a writer sends its output: a file on disk, or a Python object with `write()`.
+pub enum Sink {
+    Path(String),
+    Writer(PyWriter),
+}
+
+impl Sink {
+    /// How the output is referred to in error messages.
+    pub fn name(&self) -> &str {
+        match self {
+            Sink::Path(path) => path,
+            Sink::Writer(_) => "<file object>",
+        }
+    }
+
+    /// The file name without its extension, which some formats use to name
+    /// the dataset inside the file.
+    pub fn stem(&self) -> Option<&str> {
+        match self {
+            Sink::Path(path) => Path::new(path).file_stem().and_then(|s| s.to_str()),
+            Sink::Writer(_) => None,
+        }
+    }
+
+    /// Opens the output for writing, creating or truncating a file.
+    pub fn create(self) -> io::Result<BufWriter<Box<dyn Write + Send>>> {
+        let writer: Box<dyn Write + Send> = match self {
+            Sink::Path(path) => Box::new(File::create(path)?),
+            Sink::Writer(writer) => Box::new(writer),
+        };
+        Ok(BufWriter::new(writer))
+    }
+}
+
+impl<'py> FromPyObject<'py> for Sink {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if ob.is_instance_of::<PyString>() {
+            return Ok(Sink::Path(ob.extract()?));
+        }
+        if ob.hasattr("write")? {
+            return Ok(Sink::Writer(PyWriter(ob.clone().unbind())));
+        }
+        let path: PathBuf = ob.extract().map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyTypeError, _>("Expected a path or a file-like object with write()")
+        })?;
+        Ok(Sink::Path(path.to_string_lossy().into_owned()))
+    }
+}
+
+/// Forwards writes to a Python object's `write()` method.
+pub struct PyWriter(Py<PyAny>);
+
+impl Write for PyWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Python::with_gil(|py| {
+            let written = self.0.call_method1(py, "write", (PyBytes::new_bound(py, buf),))?;
+            // Raw streams may write less than asked; buffered ones return None or the full length
+            Ok(written.extract::<Option<usize>>(py)?.unwrap_or(buf.len()))
+        })
+        .map_err(|e: PyErr| io::Error::other(e.to_string()))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Python::with_gil(|py| {
+            if self.0.bind(py).hasattr("flush")? {
+                self.0.call_method0(py, "flush")?;
+            }
+            Ok(())
+        })
+        .map_err(|e: PyErr| io::Error::other(e.to_string()))
+    }
+}
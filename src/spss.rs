@@ -0,0 +1,603 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::Arc;
+use anyhow::{Result, Context};
+use arrow::record_batch::RecordBatch;
+use arrow_array::{ArrayRef, StringArray};
+use arrow_array::builder::{
+    Date32Builder, Float64Builder, StringDictionaryBuilder, Time64MicrosecondBuilder,
+    TimestampMillisecondBuilder,
+};
+use arrow_array::types::Int32Type;
+use arrow_schema::{Field, Schema};
+use flate2::read::ZlibDecoder;
+use crate::dataframe::DataFrame;
+
+const HEADER_LEN: usize = 176;
+
+// Days and seconds between the SPSS epoch (1582-10-14) and the Unix epoch
+const SPSS_EPOCH_OFFSET_DAYS: i64 = 141_428;
+const SPSS_EPOCH_OFFSET_SECONDS: f64 = 141_428.0 * 86_400.0;
+
+// Very long strings are split into 255-byte segments holding 252 bytes each
+const SEGMENT_DATA_LEN: usize = 252;
+const SEGMENT_WIDTH: usize = 255;
+
+/// A numeric type 2 record or the first record of a string variable.
+/// Names and labels stay raw until the file's encoding is known.
+struct RawVariable {
+    name: Vec<u8>,
+    width: usize,
+    label: Vec<u8>,
+    format: u32,
+    n_missing: i32,
+    missing: Vec<[u8; 8]>,
+}
+
+/// A variable after very long string segments have been merged back together.
+struct SpssVariable {
+    name: String,
+    label: String,
+    format: u32,
+    // Byte width of each string segment; empty for numeric variables
+    segments: Vec<usize>,
+    n_missing: i32,
+    missing: Vec<[u8; 8]>,
+    value_labels: Vec<([u8; 8], String)>,
+}
+
+impl SpssVariable {
+    fn is_numeric(&self) -> bool {
+        self.segments.is_empty()
+    }
+}
+
+/// A value label record: raw (value, label) pairs and the variables they apply to.
+struct LabelSet {
+    entries: Vec<([u8; 8], Vec<u8>)>,
+    variables: Vec<usize>,
+}
+
+enum ColumnValues {
+    Numeric(Vec<Option<f64>>),
+    Text(Vec<Option<String>>),
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    little_endian: bool,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        let slice = self.bytes.get(self.pos..self.pos + n).context("Unexpected end of SPSS file")?;
+        self.pos += n;
+        Ok(slice)
+    }
+
+    fn i32(&mut self) -> Result<i32> {
+        let b: [u8; 4] = self.take(4)?.try_into()?;
+        Ok(if self.little_endian { i32::from_le_bytes(b) } else { i32::from_be_bytes(b) })
+    }
+
+    fn i64(&mut self) -> Result<i64> {
+        let b: [u8; 8] = self.take(8)?.try_into()?;
+        Ok(if self.little_endian { i64::from_le_bytes(b) } else { i64::from_be_bytes(b) })
+    }
+
+    fn f64(&mut self) -> Result<f64> {
+        Ok(f64::from_bits(self.i64()? as u64))
+    }
+
+    fn slot(&mut self) -> Result<[u8; 8]> {
+        Ok(self.take(8)?.try_into()?)
+    }
+}
+
+/// Decodes raw text from the file, dropping the blank padding SPSS uses for
+/// fixed-width fields. Files not declared as UTF-8 are read as Latin-1.
+fn text(bytes: &[u8], utf8: bool) -> String {
+    let end = bytes.iter().rposition(|&b| b != b' ' && b != 0).map_or(0, |i| i + 1);
+    let bytes = &bytes[..end];
+    if utf8 {
+        String::from_utf8_lossy(bytes).into_owned()
+    } else {
+        bytes.iter().map(|&b| b as char).collect()
+    }
+}
+
+/// Reads an SPSS system file (.sav) or a zlib-compressed one (.zsav). With
+/// `value_labels` set, labelled variables become dictionary-encoded string columns.
+pub fn read(bytes: &[u8], value_labels: bool) -> Result<DataFrame> {
+    let magic = bytes.get(..4).context("Unexpected end of SPSS file")?;
+    if magic != b"$FL2" && magic != b"$FL3" {
+        return Err(anyhow::anyhow!("Not an SPSS system file"));
+    }
+    // The layout code is always 2 or 3, which reveals the byte order
+    let layout = bytes.get(64..68).context("Unexpected end of SPSS file")?;
+    let little_endian = matches!(i32::from_le_bytes(layout.try_into()?), 2 | 3);
+
+    let mut cur = Cursor { bytes, pos: 72, little_endian };
+    let compression = cur.i32()?;
+    let _weight_index = cur.i32()?;
+    let n_cases = cur.i32()?;
+    let bias = cur.f64()?;
+    cur.pos = HEADER_LEN;
+
+    let mut raw_variables: Vec<RawVariable> = Vec::new();
+    // Maps each 8-byte slot of a case to the variable that owns it
+    let mut slot_owner: Vec<usize> = Vec::new();
+    let mut label_sets: Vec<LabelSet> = Vec::new();
+    let mut long_names = String::new();
+    let mut very_long_strings = String::new();
+    let mut utf8 = false;
+    let mut sysmis = -f64::MAX;
+    let mut highest = f64::MAX;
+    let mut lowest = f64::MIN;
+
+    loop {
+        match cur.i32()? {
+            2 => {
+                let width = cur.i32()?;
+                let has_label = cur.i32()?;
+                let n_missing = cur.i32()?;
+                let format = cur.i32()? as u32;
+                let _write = cur.i32()?;
+                let name = cur.take(8)?.to_vec();
+                let label = if has_label == 1 {
+                    let len = cur.i32()? as usize;
+                    let label = cur.take(len)?.to_vec();
+                    cur.take((4 - len % 4) % 4)?;
+                    label
+                } else {
+                    Vec::new()
+                };
+                let missing = (0..n_missing.unsigned_abs()).map(|_| cur.slot()).collect::<Result<Vec<_>>>()?;
+                if width == -1 {
+                    // Continuation of the previous string variable
+                    let owner = *slot_owner.last().context("Malformed SPSS file: orphan continuation record")?;
+                    slot_owner.push(owner);
+                    continue;
+                }
+                slot_owner.push(raw_variables.len());
+                raw_variables.push(RawVariable { name, width: width as usize, label, format, n_missing, missing });
+            }
+            3 => {
+                let count = cur.i32()? as usize;
+                let mut entries = Vec::with_capacity(count);
+                for _ in 0..count {
+                    let value = cur.slot()?;
+                    let len = cur.take(1)?[0] as usize;
+                    let label = cur.take(len)?.to_vec();
+                    cur.take((8 - (len + 1) % 8) % 8)?;
+                    entries.push((value, label));
+                }
+                if cur.i32()? != 4 {
+                    return Err(anyhow::anyhow!("Malformed SPSS file: value labels without a variable index record"));
+                }
+                let n_vars = cur.i32()? as usize;
+                let variables = (0..n_vars)
+                    .map(|_| {
+                        let slot = cur.i32()? as usize;
+                        slot.checked_sub(1)
+                            .and_then(|s| slot_owner.get(s).copied())
+                            .context("Malformed SPSS file: value labels refer to an unknown variable")
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                label_sets.push(LabelSet { entries, variables });
+            }
+            6 => {
+                let lines = cur.i32()? as usize;
+                cur.take(lines * 80)?;
+            }
+            7 => {
+                let subtype = cur.i32()?;
+                let size = cur.i32()? as usize;
+                let count = cur.i32()? as usize;
+                let data = cur.take(size * count)?;
+                let mut ext = Cursor { bytes: data, pos: 0, little_endian };
+                match subtype {
+                    // Machine integer info: the eighth field is the code page
+                    3 if data.len() >= 32 => {
+                        ext.pos = 28;
+                        utf8 |= ext.i32()? == 65001;
+                    }
+                    4 if data.len() >= 24 => {
+                        sysmis = ext.f64()?;
+                        highest = ext.f64()?;
+                        lowest = ext.f64()?;
+                    }
+                    13 => long_names = String::from_utf8_lossy(data).into_owned(),
+                    14 => very_long_strings = String::from_utf8_lossy(data).into_owned(),
+                    20 => utf8 |= String::from_utf8_lossy(data).trim().eq_ignore_ascii_case("UTF-8"),
+                    _ => {}
+                }
+            }
+            999 => {
+                cur.i32()?;
+                break;
+            }
+            other => return Err(anyhow::anyhow!("Malformed SPSS file: unknown record type {}", other)),
+        }
+    }
+
+    let variables = merge_variables(raw_variables, &long_names, &very_long_strings, label_sets, utf8)?;
+
+    let data = match compression {
+        0 | 1 => bytes[cur.pos..].to_vec(),
+        2 => inflate_blocks(bytes, &mut cur)?,
+        other => return Err(anyhow::anyhow!("Unsupported SPSS compression: {}", other)),
+    };
+    let mut slots = SlotReader {
+        data: &data,
+        pos: 0,
+        compressed: compression != 0,
+        bias,
+        sysmis,
+        little_endian,
+        commands: [0; 8],
+        command_idx: 8,
+    };
+
+    let mut columns: Vec<ColumnValues> = variables.iter()
+        .map(|v| if v.is_numeric() { ColumnValues::Numeric(Vec::new()) } else { ColumnValues::Text(Vec::new()) })
+        .collect();
+    let mut row = 0;
+    'cases: while n_cases < 0 || row < n_cases {
+        for (i, variable) in variables.iter().enumerate() {
+            let mut next_slot = || -> Result<Option<[u8; 8]>> {
+                match slots.next()? {
+                    Some(slot) => Ok(Some(slot)),
+                    None if i == 0 => Ok(None),
+                    None => Err(anyhow::anyhow!("Malformed SPSS file: truncated case {}", row + 1)),
+                }
+            };
+            match &mut columns[i] {
+                ColumnValues::Numeric(values) => {
+                    let Some(slot) = next_slot()? else { break 'cases };
+                    let value = slot_f64(slot, little_endian);
+                    let missing = value == sysmis || value.is_nan()
+                        || is_user_missing(value, variable, little_endian, lowest, highest);
+                    values.push((!missing).then_some(value));
+                }
+                ColumnValues::Text(values) => {
+                    let mut raw = Vec::new();
+                    let last = variable.segments.len() - 1;
+                    for (s, &width) in variable.segments.iter().enumerate() {
+                        let mut segment = Vec::with_capacity(width.div_ceil(8) * 8);
+                        for _ in 0..width.div_ceil(8) {
+                            let Some(slot) = next_slot()? else { break 'cases };
+                            segment.extend_from_slice(&slot);
+                        }
+                        let used = if s < last { SEGMENT_DATA_LEN } else { width };
+                        raw.extend_from_slice(&segment[..used]);
+                    }
+                    let missing = variable.missing.iter().any(|m| text(m, utf8) == text(&raw, utf8));
+                    values.push((!missing).then(|| text(&raw, utf8)));
+                }
+            }
+        }
+        row += 1;
+    }
+
+    let mut fields = Vec::with_capacity(variables.len());
+    let mut arrays: Vec<ArrayRef> = Vec::with_capacity(variables.len());
+    for (variable, values) in variables.iter().zip(columns) {
+        let format = format_name(variable.format);
+        let array: ArrayRef = match values {
+            ColumnValues::Numeric(values) if value_labels && !variable.value_labels.is_empty() => {
+                let set: Vec<(f64, &str)> = variable.value_labels.iter()
+                    .map(|(value, label)| (slot_f64(*value, little_endian), label.as_str()))
+                    .collect();
+                numeric_labelled_array(&values, set)?
+            }
+            ColumnValues::Numeric(values) => numeric_array(&values, variable.format >> 16),
+            ColumnValues::Text(values) if value_labels && !variable.value_labels.is_empty() => {
+                let set: Vec<(String, &str)> = variable.value_labels.iter()
+                    .map(|(value, label)| (text(value, utf8), label.as_str()))
+                    .collect();
+                text_labelled_array(&values, &set)?
+            }
+            ColumnValues::Text(values) => Arc::new(StringArray::from(values)),
+        };
+
+        let mut metadata = HashMap::new();
+        if !variable.label.is_empty() {
+            metadata.insert("label".to_string(), variable.label.clone());
+        }
+        if let Some(format) = format {
+            metadata.insert("format".to_string(), format);
+        }
+        if !variable.value_labels.is_empty() {
+            let labels: serde_json::Map<String, serde_json::Value> = variable.value_labels.iter()
+                .map(|(value, label)| {
+                    let key = if variable.is_numeric() { number_text(slot_f64(*value, little_endian)) } else { text(value, utf8) };
+                    (key, serde_json::Value::String(label.clone()))
+                })
+                .collect();
+            metadata.insert("value_labels".to_string(), serde_json::Value::Object(labels).to_string());
+        }
+        fields.push(Field::new(&variable.name, array.data_type().clone(), true).with_metadata(metadata));
+        arrays.push(array);
+    }
+
+    let schema = Arc::new(Schema::new(fields));
+    let batch = RecordBatch::try_new(schema, arrays)?;
+    Ok(DataFrame { batches: vec![batch] })
+}
+
+/// Applies long variable names, joins very long string segments and attaches
+/// value label sets to their variables.
+fn merge_variables(
+    raw_variables: Vec<RawVariable>,
+    long_names: &str,
+    very_long_strings: &str,
+    label_sets: Vec<LabelSet>,
+    utf8: bool,
+) -> Result<Vec<SpssVariable>> {
+    // "SHORT=Long name" pairs separated by tabs
+    let long_names: HashMap<&str, &str> = long_names.split('\t')
+        .filter_map(|pair| pair.split_once('='))
+        .collect();
+    // "SHORT=00300" pairs terminated by NUL and tab
+    let very_long: HashMap<&str, usize> = very_long_strings.split(['\0', '\t'])
+        .filter_map(|pair| pair.split_once('='))
+        .filter_map(|(name, width)| Some((name, width.trim().parse().ok()?)))
+        .collect();
+
+    let mut labels_by_raw: HashMap<usize, Vec<([u8; 8], String)>> = HashMap::new();
+    for set in label_sets {
+        let entries: Vec<([u8; 8], String)> = set.entries.into_iter()
+            .map(|(value, label)| (value, text(&label, utf8)))
+            .collect();
+        for index in set.variables {
+            labels_by_raw.insert(index, entries.clone());
+        }
+    }
+
+    let mut variables = Vec::new();
+    let mut raw_iter = raw_variables.into_iter().enumerate();
+    while let Some((index, raw)) = raw_iter.next() {
+        let short_name = text(&raw.name, utf8);
+        let segments = match very_long.get(short_name.as_str()) {
+            Some(&width) if width > SEGMENT_WIDTH => {
+                let n_segments = width.div_ceil(SEGMENT_DATA_LEN);
+                let mut segments = vec![raw.width];
+                for _ in 1..n_segments {
+                    let (_, segment) = raw_iter.next().context("Malformed SPSS file: missing very long string segment")?;
+                    segments.push(segment.width);
+                }
+                segments
+            }
+            _ if raw.width == 0 => Vec::new(),
+            _ => vec![raw.width],
+        };
+        let name = long_names.get(short_name.as_str()).map_or(short_name.clone(), |n| n.to_string());
+        variables.push(SpssVariable {
+            name,
+            label: text(&raw.label, utf8),
+            format: raw.format,
+            segments,
+            n_missing: raw.n_missing,
+            missing: raw.missing,
+            value_labels: labels_by_raw.remove(&index).unwrap_or_default(),
+        });
+    }
+    Ok(variables)
+}
+
+/// Decompresses the zlib blocks of a .zsav file into a bytecode stream.
+fn inflate_blocks(bytes: &[u8], cur: &mut Cursor) -> Result<Vec<u8>> {
+    let _header_offset = cur.i64()?;
+    let trailer_offset = cur.i64()? as usize;
+    let _trailer_len = cur.i64()?;
+
+    let mut trailer = Cursor { bytes, pos: trailer_offset, little_endian: cur.little_endian };
+    let _bias = trailer.i64()?;
+    let _zero = trailer.i64()?;
+    let _block_size = trailer.i32()?;
+    let n_blocks = trailer.i32()? as usize;
+
+    let mut data = Vec::new();
+    for _ in 0..n_blocks {
+        let _uncompressed_offset = trailer.i64()?;
+        let compressed_offset = trailer.i64()? as usize;
+        let _uncompressed_size = trailer.i32()?;
+        let compressed_size = trailer.i32()? as usize;
+        let block = bytes.get(compressed_offset..compressed_offset + compressed_size)
+            .context("Malformed SPSS file: zlib block out of range")?;
+        ZlibDecoder::new(block).read_to_end(&mut data).context("Failed to inflate SPSS data block")?;
+    }
+    Ok(data)
+}
+
+/// Yields the 8-byte slots of the case data, expanding bytecode compression.
+struct SlotReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    compressed: bool,
+    bias: f64,
+    sysmis: f64,
+    little_endian: bool,
+    commands: [u8; 8],
+    command_idx: usize,
+}
+
+impl SlotReader<'_> {
+    fn raw(&mut self) -> Option<[u8; 8]> {
+        let slot = self.data.get(self.pos..self.pos + 8)?.try_into().ok()?;
+        self.pos += 8;
+        Some(slot)
+    }
+
+    fn number(&self, value: f64) -> [u8; 8] {
+        if self.little_endian { value.to_le_bytes() } else { value.to_be_bytes() }
+    }
+
+    fn next(&mut self) -> Result<Option<[u8; 8]>> {
+        if !self.compressed {
+            return Ok(self.raw());
+        }
+        loop {
+            if self.command_idx == 8 {
+                match self.raw() {
+                    Some(commands) => self.commands = commands,
+                    None => return Ok(None),
+                }
+                self.command_idx = 0;
+            }
+            let code = self.commands[self.command_idx];
+            self.command_idx += 1;
+            match code {
+                0 => continue,
+                1..=251 => return Ok(Some(self.number(code as f64 - self.bias))),
+                252 => return Ok(None),
+                253 => return self.raw().map(Some).context("Malformed SPSS file: truncated case data"),
+                254 => return Ok(Some([b' '; 8])),
+                255 => return Ok(Some(self.number(self.sysmis))),
+            }
+        }
+    }
+}
+
+fn slot_f64(slot: [u8; 8], little_endian: bool) -> f64 {
+    if little_endian { f64::from_le_bytes(slot) } else { f64::from_be_bytes(slot) }
+}
+
+/// Checks a value against the variable's user-missing definition: up to three
+/// discrete values (n > 0), a range (n = -2) or a range plus one value (n = -3).
+fn is_user_missing(value: f64, variable: &SpssVariable, little_endian: bool, lowest: f64, highest: f64) -> bool {
+    let missing: Vec<f64> = variable.missing.iter().map(|m| slot_f64(*m, little_endian)).collect();
+    let in_range = |low: f64, high: f64| {
+        (low == lowest || value >= low) && (high == highest || value <= high)
+    };
+    match variable.n_missing {
+        -2 => in_range(missing[0], missing[1]),
+        -3 => in_range(missing[0], missing[1]) || value == missing[2],
+        _ => missing.contains(&value),
+    }
+}
+
+fn number_text(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        value.to_string()
+    }
+}
+
+fn numeric_array(values: &[Option<f64>], format_type: u32) -> ArrayRef {
+    let n = values.len();
+    match format_type {
+        // DATE, ADATE, JDATE, MOYR, QYR, WKYR, EDATE, SDATE hold seconds since 1582-10-14
+        20 | 23 | 24 | 28 | 29 | 30 | 38 | 39 => {
+            let mut builder = Date32Builder::with_capacity(n);
+            for v in values {
+                builder.append_option(v.map(|s| ((s / 86_400.0).floor() as i64 - SPSS_EPOCH_OFFSET_DAYS) as i32));
+            }
+            Arc::new(builder.finish())
+        }
+        // DATETIME and YMDHMS
+        22 | 41 => {
+            let mut builder = TimestampMillisecondBuilder::with_capacity(n);
+            for v in values {
+                builder.append_option(v.map(|s| ((s - SPSS_EPOCH_OFFSET_SECONDS) * 1000.0).round() as i64));
+            }
+            Arc::new(builder.finish())
+        }
+        // TIME and MTIME hold seconds since midnight
+        21 | 40 => {
+            let mut builder = Time64MicrosecondBuilder::with_capacity(n);
+            for v in values {
+                builder.append_option(v.map(|s| (s * 1_000_000.0).round() as i64));
+            }
+            Arc::new(builder.finish())
+        }
+        _ => {
+            let mut builder = Float64Builder::with_capacity(n);
+            values.iter().for_each(|v| builder.append_option(*v));
+            Arc::new(builder.finish())
+        }
+    }
+}
+
+/// Builds a dictionary column whose dictionary starts with the labels in value
+/// order; values without a label keep their number as text.
+fn numeric_labelled_array(values: &[Option<f64>], mut set: Vec<(f64, &str)>) -> Result<ArrayRef> {
+    set.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let dictionary = StringArray::from_iter_values(set.iter().map(|(_, label)| *label));
+    let mut builder = StringDictionaryBuilder::<Int32Type>::new_with_dictionary(values.len(), &dictionary)?;
+    let lookup: HashMap<u64, &str> = set.iter().map(|(value, label)| (value.to_bits(), *label)).collect();
+    for value in values {
+        match value {
+            Some(v) => match lookup.get(&v.to_bits()) {
+                Some(label) => builder.append_value(label),
+                None => builder.append_value(number_text(*v)),
+            },
+            None => builder.append_null(),
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+fn text_labelled_array(values: &[Option<String>], set: &[(String, &str)]) -> Result<ArrayRef> {
+    let dictionary = StringArray::from_iter_values(set.iter().map(|(_, label)| *label));
+    let mut builder = StringDictionaryBuilder::<Int32Type>::new_with_dictionary(values.len(), &dictionary)?;
+    let lookup: HashMap<&str, &str> = set.iter().map(|(value, label)| (value.as_str(), *label)).collect();
+    for value in values {
+        match value {
+            Some(v) => builder.append_value(lookup.get(v.as_str()).copied().unwrap_or(v)),
+            None => builder.append_null(),
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Renders a packed print format (type << 16 | width << 8 | decimals) the way
+/// SPSS displays it, e.g. `F8.2` or `DATE11`.
+fn format_name(format: u32) -> Option<String> {
+    let name = match format >> 16 {
+        1 => "A",
+        2 => "AHEX",
+        3 => "COMMA",
+        4 => "DOLLAR",
+        5 => "F",
+        6 => "IB",
+        7 => "PIBHEX",
+        8 => "P",
+        9 => "PIB",
+        10 => "PK",
+        11 => "RB",
+        12 => "RBHEX",
+        15 => "Z",
+        16 => "N",
+        17 => "E",
+        20 => "DATE",
+        21 => "TIME",
+        22 => "DATETIME",
+        23 => "ADATE",
+        24 => "JDATE",
+        25 => "DTIME",
+        26 => "WKDAY",
+        27 => "MONTH",
+        28 => "MOYR",
+        29 => "QYR",
+        30 => "WKYR",
+        31 => "PCT",
+        32 => "DOT",
+        33 => "CCA",
+        34 => "CCB",
+        35 => "CCC",
+        36 => "CCD",
+        37 => "CCE",
+        38 => "EDATE",
+        39 => "SDATE",
+        40 => "MTIME",
+        41 => "YMDHMS",
+        _ => return None,
+    };
+    let width = (format >> 8) & 0xff;
+    let decimals = format & 0xff;
+    Some(if decimals > 0 { format!("{}{}.{}", name, width, decimals) } else { format!("{}{}", name, width) })
+}
@@ -0,0 +1,204 @@
+use std::sync::Arc;
+use anyhow::{Context, Result};
+use arrow::record_batch::RecordBatch;
+use arrow_array::{Array, ArrayRef, BinaryArray, BooleanArray, Float64Array, Int64Array, StringArray};
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float64Type, Int64Type};
+use arrow_cast::{cast, cast_with_options, CastOptions};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use rusqlite::types::{ToSqlOutput, Value, ValueRef};
+use rusqlite::{params_from_iter, Connection};
+use crate::dataframe::DataFrame;
+
+/// What `to_sqlite` does when the target table already exists.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum IfExists {
+    Fail,
+    Replace,
+    Append,
+}
+
+impl IfExists {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "fail" => Ok(IfExists::Fail),
+            "replace" => Ok(IfExists::Replace),
+            "append" => Ok(IfExists::Append),
+            _ => Err(anyhow::anyhow!("Invalid if_exists value: {} (expected fail, replace or append)", value)),
+        }
+    }
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Runs `query` and collects the result into a single record batch.
+///
+/// SQLite keeps a storage class per value, so each column's type comes from the
+/// classes it actually holds: integers become Int64, any real makes it Float64,
+/// any text makes it Utf8 and blobs become Binary. Columns declared as BOOLEAN,
+/// DATE, DATETIME/TIMESTAMP or TIME are converted to the matching Arrow type.
+pub fn read(conn: &Connection, query: &str) -> Result<DataFrame> {
+    let mut stmt = conn.prepare(query)?;
+    let columns: Vec<(String, Option<String>)> = stmt.columns().iter()
+        .map(|c| (c.name().to_string(), c.decl_type().map(|d| d.to_ascii_uppercase())))
+        .collect();
+
+    let mut values: Vec<Vec<Value>> = vec![Vec::new(); columns.len()];
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        for (i, column) in values.iter_mut().enumerate() {
+            column.push(row.get_ref(i)?.into());
+        }
+    }
+
+    let mut fields = Vec::with_capacity(columns.len());
+    let mut arrays = Vec::with_capacity(columns.len());
+    for ((name, decl), values) in columns.iter().zip(values) {
+        let array = column_array(&values, decl.as_deref())?;
+        fields.push(Field::new(name, array.data_type().clone(), true));
+        arrays.push(array);
+    }
+    let batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)?;
+    Ok(DataFrame { batches: vec![batch] })
+}
+
+fn column_array(values: &[Value], decl: Option<&str>) -> Result<ArrayRef> {
+    let has = |f: fn(&Value) -> bool| values.iter().any(f);
+    let (ints, reals) = (has(|v| matches!(v, Value::Integer(_))), has(|v| matches!(v, Value::Real(_))));
+    let (texts, blobs) = (has(|v| matches!(v, Value::Text(_))), has(|v| matches!(v, Value::Blob(_))));
+
+    let array: ArrayRef = if blobs && !(ints || reals || texts) {
+        Arc::new(values.iter().map(|v| match v { Value::Blob(b) => Some(b.as_slice()), _ => None }).collect::<BinaryArray>())
+    } else if texts || blobs {
+        Arc::new(values.iter()
+            .map(|v| match v {
+                Value::Null => None,
+                Value::Integer(i) => Some(i.to_string()),
+                Value::Real(r) => Some(r.to_string()),
+                Value::Text(s) => Some(s.clone()),
+                Value::Blob(b) => Some(String::from_utf8_lossy(b).into_owned()),
+            })
+            .collect::<StringArray>())
+    } else if reals {
+        Arc::new(values.iter()
+            .map(|v| match v { Value::Integer(i) => Some(*i as f64), Value::Real(r) => Some(*r), _ => None })
+            .collect::<Float64Array>())
+    } else if ints || declared_affinity(decl) == DataType::Int64 {
+        Arc::new(values.iter().map(|v| match v { Value::Integer(i) => Some(*i), _ => None }).collect::<Int64Array>())
+    } else {
+        // Only NULLs: fall back to the declared type's affinity
+        let strings = StringArray::new_null(values.len());
+        cast(&strings, &declared_affinity(decl))?
+    };
+
+    let target = match decl {
+        Some(d) if d.starts_with("BOOL") && ints && !(reals || texts || blobs) => {
+            let bools: BooleanArray = array.as_primitive::<Int64Type>().iter().map(|v| v.map(|i| i != 0)).collect();
+            return Ok(Arc::new(bools));
+        }
+        Some("DATE") => DataType::Date32,
+        Some("DATETIME") | Some("TIMESTAMP") => DataType::Timestamp(TimeUnit::Microsecond, None),
+        Some("TIME") => DataType::Time64(TimeUnit::Microsecond),
+        _ => return Ok(array),
+    };
+    if array.data_type() != &DataType::Utf8 && array.null_count() != array.len() {
+        return Ok(array);
+    }
+    // Keep the text when it doesn't parse rather than silently dropping values
+    let strict = CastOptions { safe: false, ..Default::default() };
+    Ok(cast_with_options(&array, &target, &strict).unwrap_or(array))
+}
+
+/// Arrow type for a column declared as `decl`, following SQLite's affinity rules.
+fn declared_affinity(decl: Option<&str>) -> DataType {
+    match decl {
+        Some(d) if d.contains("INT") => DataType::Int64,
+        Some(d) if d.contains("REAL") || d.contains("FLOA") || d.contains("DOUB") => DataType::Float64,
+        Some(d) if d.contains("BLOB") => DataType::Binary,
+        _ => DataType::Utf8,
+    }
+}
+
+/// SQLite column type for an Arrow type, and the type its values are cast to
+/// before binding. Dates and times are stored as ISO 8601 text.
+fn sql_type(data_type: &DataType) -> (&'static str, DataType) {
+    match data_type {
+        DataType::Boolean => ("BOOLEAN", DataType::Int64),
+        DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::Int64
+        | DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64 => ("INTEGER", DataType::Int64),
+        DataType::Float16 | DataType::Float32 | DataType::Float64
+        | DataType::Decimal128(_, _) | DataType::Decimal256(_, _) => ("REAL", DataType::Float64),
+        DataType::Binary | DataType::LargeBinary | DataType::FixedSizeBinary(_) => ("BLOB", DataType::Binary),
+        DataType::Date32 | DataType::Date64 => ("DATE", DataType::Utf8),
+        DataType::Timestamp(_, _) => ("TIMESTAMP", DataType::Utf8),
+        DataType::Time32(_) | DataType::Time64(_) => ("TIME", DataType::Utf8),
+        _ => ("TEXT", DataType::Utf8),
+    }
+}
+
+/// Inserts every row of the DataFrame into `table` inside one transaction, so a
+/// failed write leaves the database unchanged. SQLite has no multi-row bind, so
+/// the batch is one prepared statement re-bound for each row.
+pub fn write(df: &DataFrame, conn: &mut Connection, table: &str, if_exists: IfExists) -> Result<()> {
+    let schema = match df.batches.first() {
+        Some(batch) => batch.schema(),
+        None => return Err(anyhow::anyhow!("DataFrame is empty")),
+    };
+    let quoted_table = quote_identifier(table);
+    let tx = conn.transaction()?;
+
+    let exists: bool = tx.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [table],
+        |row| row.get(0),
+    )?;
+    if exists {
+        match if_exists {
+            IfExists::Fail => return Err(anyhow::anyhow!("Table already exists: {}", table)),
+            IfExists::Replace => tx.execute_batch(&format!("DROP TABLE {}", quoted_table))?,
+            IfExists::Append => {}
+        }
+    }
+    if !exists || if_exists == IfExists::Replace {
+        let columns: Vec<String> = schema.fields().iter()
+            .map(|f| format!("{} {}", quote_identifier(f.name()), sql_type(f.data_type()).0))
+            .collect();
+        tx.execute_batch(&format!("CREATE TABLE {} ({})", quoted_table, columns.join(", ")))?;
+    }
+
+    let names: Vec<String> = schema.fields().iter().map(|f| quote_identifier(f.name())).collect();
+    let placeholders = vec!["?"; names.len()].join(", ");
+    let sql = format!("INSERT INTO {} ({}) VALUES ({})", quoted_table, names.join(", "), placeholders);
+    {
+        let mut stmt = tx.prepare(&sql)?;
+        for batch in &df.batches {
+            // Cast each column once per batch rather than per value, failing on values
+            // that don't fit, such as a UInt64 above i64::MAX, instead of writing NULL
+            let strict = CastOptions { safe: false, ..Default::default() };
+            let columns = schema.fields().iter().zip(batch.columns())
+                .map(|(field, c)| cast_with_options(c, &sql_type(c.data_type()).1, &strict)
+                    .with_context(|| format!("Cannot write column {} to SQLite", field.name())))
+                .collect::<Result<Vec<ArrayRef>>>()?;
+            for row in 0..batch.num_rows() {
+                let params = columns.iter().map(|c| ToSqlOutput::Borrowed(value_ref(c, row)));
+                stmt.execute(params_from_iter(params))?;
+            }
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+fn value_ref(array: &ArrayRef, row: usize) -> ValueRef<'_> {
+    if array.is_null(row) {
+        return ValueRef::Null;
+    }
+    match array.data_type() {
+        DataType::Int64 => ValueRef::Integer(array.as_primitive::<Int64Type>().value(row)),
+        DataType::Float64 => ValueRef::Real(array.as_primitive::<Float64Type>().value(row)),
+        DataType::Binary => ValueRef::Blob(array.as_binary::<i32>().value(row)),
+        _ => ValueRef::Text(array.as_string::<i32>().value(row).as_bytes()),
+    }
+}
@@ -0,0 +1,401 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use anyhow::{Result, Context};
+use arrow::record_batch::RecordBatch;
+use arrow_array::{ArrayRef, StringArray};
+use arrow_array::builder::{
+    Date32Builder, Float32Builder, Float64Builder, Int16Builder, Int32Builder, Int8Builder,
+    StringBuilder, StringDictionaryBuilder, TimestampMillisecondBuilder,
+};
+use arrow_array::types::Int32Type;
+use arrow_schema::{Field, Schema};
+use crate::dataframe::DataFrame;
+
+// Days and milliseconds between the Stata epoch (1960-01-01) and the Unix epoch
+const STATA_EPOCH_OFFSET_DAYS: i32 = 3653;
+const STATA_EPOCH_OFFSET_MILLIS: i64 = 3653 * 86_400_000;
+
+#[derive(Clone, Copy)]
+enum StataType {
+    Str(usize),
+    StrL,
+    Double,
+    Float,
+    Long,
+    Int,
+    Byte,
+}
+
+impl StataType {
+    fn from_code(code: u16) -> Result<Self> {
+        match code {
+            1..=2045 => Ok(StataType::Str(code as usize)),
+            32768 => Ok(StataType::StrL),
+            65526 => Ok(StataType::Double),
+            65527 => Ok(StataType::Float),
+            65528 => Ok(StataType::Long),
+            65529 => Ok(StataType::Int),
+            65530 => Ok(StataType::Byte),
+            _ => Err(anyhow::anyhow!("Unknown Stata variable type code: {}", code)),
+        }
+    }
+
+    fn width(self) -> usize {
+        match self {
+            StataType::Str(n) => n,
+            StataType::StrL | StataType::Double => 8,
+            StataType::Float | StataType::Long => 4,
+            StataType::Int => 2,
+            StataType::Byte => 1,
+        }
+    }
+}
+
+struct StataVariable {
+    name: String,
+    kind: StataType,
+    format: String,
+    value_label: String,
+    label: String,
+    offset: usize,
+}
+
+/// Sequential reader over the tagged sections of a dta file.
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    little_endian: bool,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        let slice = self.bytes.get(self.pos..self.pos + n).context("Unexpected end of Stata file")?;
+        self.pos += n;
+        Ok(slice)
+    }
+
+    fn expect(&mut self, tag: &str) -> Result<()> {
+        let found = self.take(tag.len())?;
+        if found != tag.as_bytes() {
+            return Err(anyhow::anyhow!("Malformed Stata file: expected {}", tag));
+        }
+        Ok(())
+    }
+
+    fn uint(&mut self, n: usize) -> Result<u64> {
+        Ok(read_uint(self.take(n)?, self.little_endian))
+    }
+}
+
+fn read_uint(bytes: &[u8], little_endian: bool) -> u64 {
+    let mut value = 0u64;
+    if little_endian {
+        for &b in bytes.iter().rev() {
+            value = (value << 8) | b as u64;
+        }
+    } else {
+        for &b in bytes {
+            value = (value << 8) | b as u64;
+        }
+    }
+    value
+}
+
+/// Decodes a fixed-width, NUL-terminated text field. Release 117 files use
+/// Latin-1; 118 and later use UTF-8.
+fn text(bytes: &[u8], utf8: bool) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    let bytes = &bytes[..end];
+    if utf8 {
+        String::from_utf8_lossy(bytes).into_owned()
+    } else {
+        bytes.iter().map(|&b| b as char).collect()
+    }
+}
+
+/// Reads a Stata dta file in release 117, 118 or 119 format. With `value_labels`
+/// set, labelled numeric variables become dictionary-encoded string columns.
+pub fn read(bytes: &[u8], value_labels: bool) -> Result<DataFrame> {
+    let mut cur = Cursor { bytes, pos: 0, little_endian: true };
+
+    cur.expect("<stata_dta><header><release>")?;
+    let release: u32 = std::str::from_utf8(cur.take(3)?)?.parse()
+        .context("Unsupported Stata file: only releases 117, 118 and 119 are supported")?;
+    if !(117..=119).contains(&release) {
+        return Err(anyhow::anyhow!("Unsupported Stata release: {} (expected 117, 118 or 119)", release));
+    }
+    let utf8 = release >= 118;
+    cur.expect("</release><byteorder>")?;
+    cur.little_endian = match cur.take(3)? {
+        b"LSF" => true,
+        b"MSF" => false,
+        _ => return Err(anyhow::anyhow!("Malformed Stata file: unknown byte order")),
+    };
+    cur.expect("</byteorder><K>")?;
+    let n_vars = cur.uint(if release == 119 { 4 } else { 2 })? as usize;
+    cur.expect("</K><N>")?;
+    let n_rows = cur.uint(if release == 117 { 4 } else { 8 })? as usize;
+    cur.expect("</N><label>")?;
+    let label_len = cur.uint(if release == 117 { 1 } else { 2 })? as usize;
+    cur.take(label_len)?;
+    cur.expect("</label><timestamp>")?;
+    let stamp_len = cur.uint(1)? as usize;
+    cur.take(stamp_len)?;
+    cur.expect("</timestamp></header><map>")?;
+    let mut map = [0usize; 14];
+    for entry in map.iter_mut() {
+        *entry = cur.uint(8)? as usize;
+    }
+
+    let (name_len, format_len, label_len) = if utf8 { (129, 57, 321) } else { (33, 49, 81) };
+
+    cur.pos = map[2];
+    cur.expect("<variable_types>")?;
+    let mut kinds = Vec::with_capacity(n_vars);
+    for _ in 0..n_vars {
+        kinds.push(StataType::from_code(cur.uint(2)? as u16)?);
+    }
+
+    let read_texts = |cur: &mut Cursor, section: usize, tag: &str, width: usize| -> Result<Vec<String>> {
+        cur.pos = map[section];
+        cur.expect(&format!("<{}>", tag))?;
+        (0..n_vars).map(|_| Ok(text(cur.take(width)?, utf8))).collect()
+    };
+    let names = read_texts(&mut cur, 3, "varnames", name_len)?;
+    let formats = read_texts(&mut cur, 5, "formats", format_len)?;
+    let label_names = read_texts(&mut cur, 6, "value_label_names", name_len)?;
+    let labels = read_texts(&mut cur, 7, "variable_labels", label_len)?;
+
+    let mut offset = 0;
+    let mut variables = Vec::with_capacity(n_vars);
+    for (i, kind) in kinds.into_iter().enumerate() {
+        variables.push(StataVariable {
+            name: names[i].clone(),
+            kind,
+            format: formats[i].clone(),
+            value_label: label_names[i].clone(),
+            label: labels[i].clone(),
+            offset,
+        });
+        offset += kind.width();
+    }
+    let row_len = offset;
+
+    cur.pos = map[9];
+    cur.expect("<data>")?;
+    let data = cur.take(n_rows * row_len)?;
+
+    let strls = if variables.iter().any(|v| matches!(v.kind, StataType::StrL)) {
+        read_strls(&mut Cursor { bytes, pos: map[10], little_endian: cur.little_endian }, release, utf8)?
+    } else {
+        HashMap::new()
+    };
+    let label_sets = if value_labels {
+        read_value_labels(&mut Cursor { bytes, pos: map[11], little_endian: cur.little_endian }, name_len, utf8)?
+    } else {
+        HashMap::new()
+    };
+
+    let mut fields = Vec::with_capacity(n_vars);
+    let mut arrays: Vec<ArrayRef> = Vec::with_capacity(n_vars);
+    for variable in &variables {
+        let cells = (0..n_rows).map(|row| {
+            let start = row * row_len + variable.offset;
+            &data[start..start + variable.kind.width()]
+        });
+        let little = cur.little_endian;
+
+        let array: ArrayRef = match variable.kind {
+            StataType::Str(_) => {
+                let mut builder = StringBuilder::with_capacity(n_rows, n_rows * 8);
+                for cell in cells {
+                    builder.append_value(text(cell, utf8));
+                }
+                Arc::new(builder.finish())
+            }
+            StataType::StrL => {
+                let mut builder = StringBuilder::with_capacity(n_rows, n_rows * 8);
+                for cell in cells {
+                    let key = strl_key(cell, release, little);
+                    // (0, 0) is how Stata stores an empty strL
+                    match strls.get(&key) {
+                        Some(value) => builder.append_value(value),
+                        None => builder.append_value(""),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            kind => {
+                let values: Vec<Option<f64>> = cells.map(|cell| numeric_value(cell, kind, little)).collect();
+                match label_sets.get(&variable.value_label) {
+                    Some(set) => labelled_array(&values, set)?,
+                    None => numeric_array(&values, kind, &variable.format),
+                }
+            }
+        };
+
+        let mut metadata = HashMap::new();
+        if !variable.label.is_empty() {
+            metadata.insert("label".to_string(), variable.label.clone());
+        }
+        if !variable.format.is_empty() {
+            metadata.insert("format".to_string(), variable.format.clone());
+        }
+        if !variable.value_label.is_empty() {
+            metadata.insert("value_label".to_string(), variable.value_label.clone());
+        }
+        fields.push(Field::new(&variable.name, array.data_type().clone(), true).with_metadata(metadata));
+        arrays.push(array);
+    }
+
+    let schema = Arc::new(Schema::new(fields));
+    let batch = RecordBatch::try_new(schema, arrays)?;
+    Ok(DataFrame { batches: vec![batch] })
+}
+
+/// Decodes a numeric cell, mapping Stata's system and extended missing values
+/// (`.`, `.a`-`.z`) to None.
+fn numeric_value(cell: &[u8], kind: StataType, little_endian: bool) -> Option<f64> {
+    let raw = read_uint(cell, little_endian);
+    match kind {
+        StataType::Byte => {
+            let v = raw as u8 as i8;
+            (v <= 100).then_some(v as f64)
+        }
+        StataType::Int => {
+            let v = raw as u16 as i16;
+            (v <= 32740).then_some(v as f64)
+        }
+        StataType::Long => {
+            let v = raw as u32 as i32;
+            (v <= 2_147_483_620).then_some(v as f64)
+        }
+        StataType::Float => {
+            let v = f32::from_bits(raw as u32);
+            (v < 1.701e38).then_some(v as f64)
+        }
+        StataType::Double => {
+            let v = f64::from_bits(raw);
+            (v < 8.988e307).then_some(v)
+        }
+        StataType::Str(_) | StataType::StrL => None,
+    }
+}
+
+fn numeric_array(values: &[Option<f64>], kind: StataType, format: &str) -> ArrayRef {
+    let n = values.len();
+    // %td is days and %tc/%tC milliseconds since 1960-01-01
+    if format.starts_with("%td") || format.starts_with("%-td") {
+        let mut builder = Date32Builder::with_capacity(n);
+        for v in values {
+            builder.append_option(v.map(|d| d as i32 - STATA_EPOCH_OFFSET_DAYS));
+        }
+        return Arc::new(builder.finish());
+    }
+    if format.starts_with("%tc") || format.starts_with("%tC") || format.starts_with("%-tc") || format.starts_with("%-tC") {
+        let mut builder = TimestampMillisecondBuilder::with_capacity(n);
+        for v in values {
+            builder.append_option(v.map(|ms| ms as i64 - STATA_EPOCH_OFFSET_MILLIS));
+        }
+        return Arc::new(builder.finish());
+    }
+
+    match kind {
+        StataType::Byte => {
+            let mut builder = Int8Builder::with_capacity(n);
+            values.iter().for_each(|v| builder.append_option(v.map(|v| v as i8)));
+            Arc::new(builder.finish())
+        }
+        StataType::Int => {
+            let mut builder = Int16Builder::with_capacity(n);
+            values.iter().for_each(|v| builder.append_option(v.map(|v| v as i16)));
+            Arc::new(builder.finish())
+        }
+        StataType::Long => {
+            let mut builder = Int32Builder::with_capacity(n);
+            values.iter().for_each(|v| builder.append_option(v.map(|v| v as i32)));
+            Arc::new(builder.finish())
+        }
+        StataType::Float => {
+            let mut builder = Float32Builder::with_capacity(n);
+            values.iter().for_each(|v| builder.append_option(v.map(|v| v as f32)));
+            Arc::new(builder.finish())
+        }
+        _ => {
+            let mut builder = Float64Builder::with_capacity(n);
+            values.iter().for_each(|v| builder.append_option(*v));
+            Arc::new(builder.finish())
+        }
+    }
+}
+
+/// Builds a dictionary column from value codes. The dictionary starts with the
+/// labels in code order; codes without a label keep their number as text.
+fn labelled_array(values: &[Option<f64>], set: &[(i32, String)]) -> Result<ArrayRef> {
+    let dictionary = StringArray::from_iter_values(set.iter().map(|(_, label)| label.as_str()));
+    let mut builder = StringDictionaryBuilder::<Int32Type>::new_with_dictionary(values.len(), &dictionary)?;
+    let lookup: HashMap<i32, &str> = set.iter().map(|(code, label)| (*code, label.as_str())).collect();
+    for value in values {
+        match value {
+            Some(v) if v.fract() == 0.0 => match lookup.get(&(*v as i32)) {
+                Some(label) => builder.append_value(label),
+                None => builder.append_value((*v as i64).to_string()),
+            },
+            Some(v) => builder.append_value(v.to_string()),
+            None => builder.append_null(),
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+fn strl_key(cell: &[u8], release: u32, little_endian: bool) -> (u64, u64) {
+    // (v, o) is split 4+4 bytes in 117, 2+6 in 118 and 3+5 in 119
+    let v_len = match release {
+        117 => 4,
+        118 => 2,
+        _ => 3,
+    };
+    (read_uint(&cell[..v_len], little_endian), read_uint(&cell[v_len..], little_endian))
+}
+
+fn read_strls(cur: &mut Cursor, release: u32, utf8: bool) -> Result<HashMap<(u64, u64), String>> {
+    let mut strls = HashMap::new();
+    cur.expect("<strls>")?;
+    while cur.bytes.get(cur.pos..cur.pos + 3) == Some(b"GSO") {
+        cur.pos += 3;
+        let v = cur.uint(4)?;
+        let o = cur.uint(if release == 117 { 4 } else { 8 })?;
+        let kind = cur.uint(1)?;
+        let len = cur.uint(4)? as usize;
+        let data = cur.take(len)?;
+        // Type 130 is NUL-terminated text, 129 is binary
+        let value = if kind == 130 { text(data, utf8) } else { String::from_utf8_lossy(data).into_owned() };
+        strls.insert((v, o), value);
+    }
+    Ok(strls)
+}
+
+fn read_value_labels(cur: &mut Cursor, name_len: usize, utf8: bool) -> Result<HashMap<String, Vec<(i32, String)>>> {
+    let mut sets = HashMap::new();
+    cur.expect("<value_labels>")?;
+    while cur.bytes.get(cur.pos..cur.pos + 5) == Some(b"<lbl>") {
+        cur.pos += 5;
+        let _len = cur.uint(4)?;
+        let name = text(cur.take(name_len)?, utf8);
+        cur.take(3)?;
+        let n = cur.uint(4)? as usize;
+        let text_len = cur.uint(4)? as usize;
+        let offsets = (0..n).map(|_| cur.uint(4).map(|o| o as usize)).collect::<Result<Vec<_>>>()?;
+        let codes = (0..n).map(|_| cur.uint(4).map(|v| v as u32 as i32)).collect::<Result<Vec<_>>>()?;
+        let txt = cur.take(text_len)?;
+        let mut entries: Vec<(i32, String)> = codes.into_iter()
+            .zip(offsets)
+            .map(|(code, off)| (code, text(txt.get(off..).unwrap_or_default(), utf8)))
+            .collect();
+        entries.sort_by_key(|(code, _)| *code);
+        sets.insert(name, entries);
+        cur.expect("</lbl>")?;
+    }
+    Ok(sets)
+}
+
@@ -0,0 +1,704 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use anyhow::{Result, Context};
+use arrow::record_batch::RecordBatch;
+use arrow_array::{Array, ArrayRef, Float64Array, StringArray};
+use arrow_array::builder::{Date32Builder, Float64Builder, StringBuilder, Time64MicrosecondBuilder, TimestampMillisecondBuilder};
+use arrow_cast::cast;
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use crate::dataframe::DataFrame;
+use crate::io::{sas_date_days, sas_datetime_millis, sas_time_micros, SAS_EPOCH_OFFSET_DAYS, SAS_EPOCH_OFFSET_SECONDS};
+
+// SAS transport files are made of 80-byte card images
+const RECORD_LEN: usize = 80;
+const NAMESTR_LEN: usize = 140;
+
+/// XPORT layout version: 5 is the classic FDA submission format, 8 lifts the
+/// 8-character name, 40-character label and 200-byte character limits.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum XportVersion {
+    V5,
+    V8,
+}
+
+impl XportVersion {
+    pub fn from_number(version: u8) -> Result<Self> {
+        match version {
+            5 => Ok(XportVersion::V5),
+            8 => Ok(XportVersion::V8),
+            _ => Err(anyhow::anyhow!("Unsupported XPORT version: {} (expected 5 or 8)", version)),
+        }
+    }
+
+    fn max_name_len(self) -> usize {
+        match self {
+            XportVersion::V5 => 8,
+            XportVersion::V8 => 32,
+        }
+    }
+
+    fn max_label_len(self) -> usize {
+        match self {
+            XportVersion::V5 => 40,
+            XportVersion::V8 => 256,
+        }
+    }
+
+    fn max_char_len(self) -> usize {
+        match self {
+            XportVersion::V5 => 200,
+            XportVersion::V8 => 32767,
+        }
+    }
+}
+
+struct XportVariable {
+    name: String,
+    label: String,
+    format: String,
+    format_width: u16,
+    format_decimals: u16,
+    numeric: bool,
+    length: usize,
+    position: usize,
+}
+
+impl XportVariable {
+    fn format_string(&self) -> Option<String> {
+        if self.format.is_empty() && self.format_width == 0 {
+            return None;
+        }
+        let width = if self.format_width > 0 { self.format_width.to_string() } else { String::new() };
+        let decimals = if self.format_decimals > 0 { self.format_decimals.to_string() } else { String::new() };
+        Some(format!("{}{}.{}", self.format, width, decimals))
+    }
+
+    fn data_type(&self) -> DataType {
+        if !self.numeric {
+            return DataType::Utf8;
+        }
+        match temporal_kind(&self.format) {
+            Some(TemporalKind::Date) => DataType::Date32,
+            Some(TemporalKind::DateTime) => DataType::Timestamp(TimeUnit::Millisecond, None),
+            Some(TemporalKind::Time) => DataType::Time64(TimeUnit::Microsecond),
+            None => DataType::Float64,
+        }
+    }
+
+    fn field(&self) -> Field {
+        let mut metadata = HashMap::new();
+        if !self.label.is_empty() {
+            metadata.insert("label".to_string(), self.label.clone());
+        }
+        if let Some(format) = self.format_string() {
+            metadata.insert("format".to_string(), format);
+        }
+        metadata.insert("length".to_string(), self.length.to_string());
+        Field::new(&self.name, self.data_type(), true).with_metadata(metadata)
+    }
+}
+
+enum TemporalKind {
+    Date,
+    DateTime,
+    Time,
+}
+
+/// Classifies a SAS format name as a date, datetime or time display format.
+fn temporal_kind(format: &str) -> Option<TemporalKind> {
+    let name = format.trim().trim_end_matches('.').to_ascii_uppercase();
+    if name.is_empty() || name.starts_with('$') {
+        return None;
+    }
+    if name.contains("DATETIME") || name.starts_with("DT") || name.starts_with("E8601DT") || name.starts_with("B8601DT") {
+        return Some(TemporalKind::DateTime);
+    }
+    if name.contains("TIME") || name.starts_with("HHMM") || name.starts_with("TOD") || name.starts_with("E8601TM") || name.starts_with("B8601TM") {
+        return Some(TemporalKind::Time);
+    }
+    const DATE_FORMATS: [&str; 16] = [
+        "DATE", "DDMMYY", "MMDDYY", "YYMMDD", "YYMM", "YYQ", "MONYY", "MONNAME", "MONTH",
+        "WEEKDATE", "WORDDATE", "JULIAN", "YEAR", "E8601DA", "B8601DA", "NLDATE",
+    ];
+    if DATE_FORMATS.iter().any(|prefix| name.starts_with(prefix)) {
+        return Some(TemporalKind::Date);
+    }
+    None
+}
+
+fn record_name(record: &[u8]) -> Option<&str> {
+    if record.len() < 48 || !record.starts_with(b"HEADER RECORD*******") {
+        return None;
+    }
+    std::str::from_utf8(&record[20..28]).ok().map(str::trim_end)
+}
+
+fn text(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).trim_end_matches([' ', '\0']).to_string()
+}
+
+fn be_u16(bytes: &[u8]) -> u16 {
+    u16::from_be_bytes([bytes[0], bytes[1]])
+}
+
+fn be_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn header_number(record: &[u8], start: usize, end: usize) -> usize {
+    String::from_utf8_lossy(&record[start..end]).trim().parse().unwrap_or(0)
+}
+
+/// Converts an IBM System/370 hexadecimal float of 2 to 8 bytes into an f64.
+/// Returns None for SAS missing values (`.`, `._` and `.A`-`.Z`).
+pub fn ibm_to_f64(bytes: &[u8]) -> Option<f64> {
+    let mut buf = [0u8; 8];
+    let n = bytes.len().min(8);
+    buf[..n].copy_from_slice(&bytes[..n]);
+
+    let fraction = u64::from_be_bytes(buf) & 0x00ff_ffff_ffff_ffff;
+    if fraction == 0 {
+        let first = buf[0];
+        if first == b'.' || first == b'_' || first.is_ascii_uppercase() {
+            return None;
+        }
+        return Some(0.0);
+    }
+
+    let sign = if buf[0] & 0x80 != 0 { -1.0 } else { 1.0 };
+    let exponent = (buf[0] & 0x7f) as i32 - 64;
+    Some(sign * fraction as f64 * 2f64.powi(4 * exponent - 56))
+}
+
+/// Converts an f64 into an 8-byte IBM System/370 hexadecimal float. NaN is
+/// written as the SAS missing value `.`.
+pub fn f64_to_ibm(value: f64) -> Result<[u8; 8]> {
+    if value.is_nan() {
+        return Ok([b'.', 0, 0, 0, 0, 0, 0, 0]);
+    }
+    if value == 0.0 {
+        return Ok([0; 8]);
+    }
+    if value.is_infinite() {
+        return Err(anyhow::anyhow!("Cannot represent {} in XPORT format", value));
+    }
+
+    let bits = value.to_bits();
+    let sign = ((bits >> 63) as u8) << 7;
+    let biased = ((bits >> 52) & 0x7ff) as i32;
+    if biased == 0 {
+        // IEEE subnormals are far below the smallest IBM float
+        return Ok([0; 8]);
+    }
+    let mantissa = (bits & 0x000f_ffff_ffff_ffff) | 0x0010_0000_0000_0000;
+
+    // value = (mantissa / 2^53) * 2^exp2 with the fraction in [0.5, 1); IBM wants
+    // 16^exp16 with the fraction in [1/16, 1), so shift by the remainder
+    let exp2 = biased - 1022;
+    let exp16 = (exp2 + 3).div_euclid(4);
+    let shift = 4 * exp16 - exp2;
+    let fraction = mantissa << (3 - shift);
+
+    let ibm_exponent = exp16 + 64;
+    if ibm_exponent > 127 {
+        return Err(anyhow::anyhow!("Value {} is too large for XPORT format", value));
+    }
+    if ibm_exponent < 0 {
+        return Ok([0; 8]);
+    }
+
+    let mut out = fraction.to_be_bytes();
+    out[0] = sign | ibm_exponent as u8;
+    Ok(out)
+}
+
+/// Reads the first member of a SAS transport (XPORT v5 or v8) file.
+pub fn read(bytes: &[u8]) -> Result<DataFrame> {
+    let record = |i: usize| -> Result<&[u8]> {
+        bytes.get(i * RECORD_LEN..(i + 1) * RECORD_LEN).context("Unexpected end of XPORT file")
+    };
+
+    let version = match record_name(record(0)?) {
+        Some("LIBRARY") => XportVersion::V5,
+        Some("LIBV8") => XportVersion::V8,
+        _ => return Err(anyhow::anyhow!("Not a SAS transport file")),
+    };
+
+    // Library header and its two real-header records, then the member header
+    let member = record(3)?;
+    if !matches!(record_name(member), Some("MEMBER") | Some("MEMBV8")) {
+        return Err(anyhow::anyhow!("XPORT file does not contain a member header"));
+    }
+    let namestr_len = match header_number(member, 74, 78) {
+        0 => NAMESTR_LEN,
+        n => n,
+    };
+    if namestr_len < 88 {
+        return Err(anyhow::anyhow!("Invalid XPORT namestr length {}", namestr_len));
+    }
+
+    // Descriptor header and two member records, then the namestr header
+    let namestr_header = record(7)?;
+    if !matches!(record_name(namestr_header), Some("NAMESTR") | Some("NAMSTV8")) {
+        return Err(anyhow::anyhow!("XPORT file does not contain a namestr header"));
+    }
+    let var_count = header_number(namestr_header, 48, 58);
+
+    let mut offset = 8 * RECORD_LEN;
+    let mut variables = Vec::with_capacity(var_count);
+    for _ in 0..var_count {
+        let ns = bytes.get(offset..offset + namestr_len).context("Truncated XPORT namestr records")?;
+        let short_name = text(&ns[8..16]);
+        let name = match version {
+            XportVersion::V8 if namestr_len >= 120 => {
+                let long_name = text(&ns[88..120]);
+                if long_name.is_empty() { short_name } else { long_name }
+            }
+            _ => short_name,
+        };
+        variables.push(XportVariable {
+            name,
+            label: text(&ns[16..56]),
+            format: text(&ns[56..64]),
+            format_width: be_u16(&ns[64..66]),
+            format_decimals: be_u16(&ns[66..68]),
+            numeric: be_u16(&ns[0..2]) == 1,
+            length: be_u16(&ns[4..6]) as usize,
+            position: be_u32(&ns[84..88]) as usize,
+        });
+        offset += namestr_len;
+    }
+    offset = offset.div_ceil(RECORD_LEN) * RECORD_LEN;
+
+    let row_len: usize = variables.iter().map(|v| v.length).sum();
+    if row_len == 0 {
+        return Err(anyhow::anyhow!("XPORT member has no variables"));
+    }
+    if let Some(variable) = variables.iter().find(|v| v.position + v.length > row_len) {
+        return Err(anyhow::anyhow!(
+            "XPORT variable {} at position {} with length {} lies outside the {}-byte observation",
+            variable.name, variable.position, variable.length, row_len
+        ));
+    }
+
+    // Version 8 stores labels longer than 40 characters in their own section
+    let mut obs_count = None;
+    loop {
+        let header = record(offset / RECORD_LEN)?;
+        offset += RECORD_LEN;
+        match record_name(header) {
+            Some("LABELV8") | Some("LABELV9") => {
+                let extended = record_name(header) == Some("LABELV9");
+                let label_count = header_number(header, 48, 58);
+                for _ in 0..label_count {
+                    let head_len = if extended { 10 } else { 6 };
+                    let head = bytes.get(offset..offset + head_len).context("Truncated XPORT label records")?;
+                    let var_index = be_u16(&head[0..2]) as usize;
+                    let name_len = be_u16(&head[2..4]) as usize;
+                    let label_len = be_u16(&head[4..6]) as usize;
+                    let (format_len, informat_len) = if extended {
+                        (be_u16(&head[6..8]) as usize, be_u16(&head[8..10]) as usize)
+                    } else {
+                        (0, 0)
+                    };
+                    let start = offset + head_len;
+                    let end = start + name_len + label_len + format_len + informat_len;
+                    let body = bytes.get(start..end).context("Truncated XPORT label records")?;
+                    if let Some(variable) = var_index.checked_sub(1).and_then(|i| variables.get_mut(i)) {
+                        variable.label = text(&body[name_len..name_len + label_len]);
+                    }
+                    offset = end;
+                }
+                offset = offset.div_ceil(RECORD_LEN) * RECORD_LEN;
+            }
+            Some("OBS") => break,
+            Some("OBSV8") => {
+                obs_count = Some(header_number(header, 48, 63));
+                break;
+            }
+            _ => return Err(anyhow::anyhow!("XPORT file does not contain an observation header")),
+        }
+    }
+
+    // Observations run until the next member header or the end of the file
+    let mut data_end = bytes.len();
+    let mut pos = offset;
+    while pos + RECORD_LEN <= bytes.len() {
+        if matches!(record_name(&bytes[pos..pos + RECORD_LEN]), Some("MEMBER") | Some("MEMBV8")) {
+            data_end = pos;
+            break;
+        }
+        pos += RECORD_LEN;
+    }
+    let data = &bytes[offset.min(data_end)..data_end];
+
+    let n_rows = match obs_count {
+        Some(n) if n > 0 => n.min(data.len() / row_len),
+        _ => {
+            // Version 5 has no row count; trailing rows of blank padding are dropped
+            let mut n = data.len() / row_len;
+            while n > 0 && data[(n - 1) * row_len..n * row_len].iter().all(|&b| b == b' ') {
+                n -= 1;
+            }
+            n
+        }
+    };
+
+    let schema = Arc::new(Schema::new(variables.iter().map(XportVariable::field).collect::<Vec<_>>()));
+    let mut arrays: Vec<ArrayRef> = Vec::with_capacity(variables.len());
+    for (variable, field) in variables.iter().zip(schema.fields()) {
+        let cell = |row: usize| &data[row * row_len + variable.position..row * row_len + variable.position + variable.length];
+        let array: ArrayRef = match field.data_type() {
+            DataType::Utf8 => {
+                let mut builder = StringBuilder::with_capacity(n_rows, n_rows * variable.length);
+                for row in 0..n_rows {
+                    builder.append_value(text(cell(row)));
+                }
+                Arc::new(builder.finish())
+            }
+            DataType::Date32 => {
+                let mut builder = Date32Builder::with_capacity(n_rows);
+                for row in 0..n_rows {
+                    builder.append_option(ibm_to_f64(cell(row)).map(sas_date_days));
+                }
+                Arc::new(builder.finish())
+            }
+            DataType::Timestamp(_, _) => {
+                let mut builder = TimestampMillisecondBuilder::with_capacity(n_rows);
+                for row in 0..n_rows {
+                    builder.append_option(ibm_to_f64(cell(row)).map(sas_datetime_millis));
+                }
+                Arc::new(builder.finish())
+            }
+            DataType::Time64(_) => {
+                let mut builder = Time64MicrosecondBuilder::with_capacity(n_rows);
+                for row in 0..n_rows {
+                    builder.append_option(ibm_to_f64(cell(row)).map(sas_time_micros));
+                }
+                Arc::new(builder.finish())
+            }
+            _ => {
+                let mut builder = Float64Builder::with_capacity(n_rows);
+                for row in 0..n_rows {
+                    builder.append_option(ibm_to_f64(cell(row)));
+                }
+                Arc::new(builder.finish())
+            }
+        };
+        arrays.push(array);
+    }
+
+    let batch = RecordBatch::try_new(schema, arrays)?;
+    Ok(DataFrame { batches: vec![batch] })
+}
+
+/// A DataFrame column prepared for XPORT output: numeric columns are converted to
+/// SAS units as Float64, character columns to Utf8.
+struct OutputColumn {
+    variable: XportVariable,
+    batches: Vec<ArrayRef>,
+}
+
+fn header_record(name: &str, numbers: &str) -> Vec<u8> {
+    pad_record(format!("HEADER RECORD*******{:<8}HEADER RECORD!!!!!!!{}", name, numbers).into_bytes())
+}
+
+fn pad_record(mut record: Vec<u8>) -> Vec<u8> {
+    record.resize(record.len().div_ceil(RECORD_LEN).max(1) * RECORD_LEN, b' ');
+    record
+}
+
+fn padded(value: &str, len: usize) -> Vec<u8> {
+    let mut bytes = value.as_bytes().to_vec();
+    bytes.resize(len, b' ');
+    bytes
+}
+
+/// Formats the current time as the `ddMMMyy:hh:mm:ss` stamp used in XPORT headers.
+fn sas_timestamp() -> String {
+    const MONTHS: [&str; 12] = ["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0);
+    let (days, rem) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+
+    // Civil-from-days conversion for the proleptic Gregorian calendar
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:02}{}{:02}:{:02}:{:02}:{:02}",
+        day, MONTHS[(month - 1) as usize], year % 100, rem / 3600, rem % 3600 / 60, rem % 60
+    )
+}
+
+/// Splits a SAS format string such as "DATE9." or "8.2" into name, width and decimals.
+fn parse_format(format: &str) -> (String, u16, u16) {
+    let format = format.trim();
+    let (body, decimals) = match format.rsplit_once('.') {
+        Some((body, decimals)) => (body, decimals.parse().unwrap_or(0)),
+        None => (format, 0),
+    };
+    let name_end = body.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    let width = body[name_end..].parse().unwrap_or(0);
+    (body[..name_end].to_string(), width, decimals)
+}
+
+fn output_column(field: &Field, arrays: Vec<ArrayRef>, version: XportVersion) -> Result<OutputColumn> {
+    let name = field.name();
+    if name.is_empty() || name.len() > version.max_name_len() {
+        return Err(anyhow::anyhow!(
+            "Column name '{}' must be 1 to {} characters for this XPORT version", name, version.max_name_len()
+        ));
+    }
+    let label = field.metadata().get("label").cloned().unwrap_or_default();
+    if label.len() > version.max_label_len() {
+        return Err(anyhow::anyhow!(
+            "Label of column '{}' is longer than {} characters", name, version.max_label_len()
+        ));
+    }
+
+    let (default_format, batches): (&str, Vec<ArrayRef>) = match field.data_type() {
+        DataType::Utf8 | DataType::LargeUtf8 => {
+            ("", arrays.iter().map(|a| cast(a, &DataType::Utf8)).collect::<std::result::Result<_, _>>()?)
+        }
+        DataType::Date32 | DataType::Date64 => {
+            let converted = arrays.iter().map(|a| {
+                let days = cast(&cast(a, &DataType::Date32)?, &DataType::Int32)?;
+                let days = cast(&days, &DataType::Float64)?;
+                let days = days.as_any().downcast_ref::<Float64Array>().unwrap();
+                Ok(Arc::new(days.unary::<_, arrow_array::types::Float64Type>(|d| d + SAS_EPOCH_OFFSET_DAYS as f64)) as ArrayRef)
+            }).collect::<Result<_>>()?;
+            ("DATE9", converted)
+        }
+        DataType::Timestamp(_, _) => {
+            let converted = arrays.iter().map(|a| {
+                let millis = cast(&cast(a, &DataType::Timestamp(TimeUnit::Millisecond, None))?, &DataType::Int64)?;
+                let millis = cast(&millis, &DataType::Float64)?;
+                let millis = millis.as_any().downcast_ref::<Float64Array>().unwrap();
+                Ok(Arc::new(millis.unary::<_, arrow_array::types::Float64Type>(|ms| ms / 1000.0 + SAS_EPOCH_OFFSET_SECONDS as f64)) as ArrayRef)
+            }).collect::<Result<_>>()?;
+            ("DATETIME20", converted)
+        }
+        DataType::Time32(_) | DataType::Time64(_) => {
+            let converted = arrays.iter().map(|a| {
+                let micros = cast(&cast(a, &DataType::Time64(TimeUnit::Microsecond))?, &DataType::Int64)?;
+                let micros = cast(&micros, &DataType::Float64)?;
+                let micros = micros.as_any().downcast_ref::<Float64Array>().unwrap();
+                Ok(Arc::new(micros.unary::<_, arrow_array::types::Float64Type>(|us| us / 1_000_000.0)) as ArrayRef)
+            }).collect::<Result<_>>()?;
+            ("TIME8", converted)
+        }
+        dt if dt.is_numeric() || *dt == DataType::Boolean => {
+            ("", arrays.iter().map(|a| cast(a, &DataType::Float64)).collect::<std::result::Result<_, _>>()?)
+        }
+        dt => return Err(anyhow::anyhow!("Column '{}' has type {} which cannot be written to XPORT", name, dt)),
+    };
+
+    let numeric = !matches!(field.data_type(), DataType::Utf8 | DataType::LargeUtf8);
+    let length = if numeric {
+        8
+    } else {
+        let longest = batches.iter()
+            .map(|a| {
+                let strings = a.as_any().downcast_ref::<StringArray>().unwrap();
+                strings.iter().flatten().map(str::len).max().unwrap_or(0)
+            })
+            .max()
+            .unwrap_or(0);
+        if longest > version.max_char_len() {
+            return Err(anyhow::anyhow!(
+                "Column '{}' has values longer than {} bytes", name, version.max_char_len()
+            ));
+        }
+        longest.max(1)
+    };
+
+    // An explicit SAS format in the field metadata wins over the type's default
+    let (format, format_width, format_decimals) = match field.metadata().get("format") {
+        Some(format) => parse_format(format),
+        None if default_format.is_empty() => (String::new(), 0, 0),
+        None => parse_format(&format!("{}.", default_format)),
+    };
+
+    Ok(OutputColumn {
+        variable: XportVariable {
+            name: name.clone(),
+            label,
+            format,
+            format_width,
+            format_decimals,
+            numeric,
+            length,
+            position: 0,
+        },
+        batches,
+    })
+}
+
+/// Writes a DataFrame as a single-member SAS transport file.
+pub fn write<W: Write>(df: &DataFrame, out: &mut W, member: &str, version: XportVersion) -> Result<()> {
+    if df.batches.is_empty() {
+        return Err(anyhow::anyhow!("DataFrame is empty"));
+    }
+    let member = member.to_ascii_uppercase();
+    if member.is_empty() || member.len() > version.max_name_len() {
+        return Err(anyhow::anyhow!(
+            "Dataset name '{}' must be 1 to {} characters for this XPORT version", member, version.max_name_len()
+        ));
+    }
+
+    let schema = df.batches[0].schema();
+    let mut columns = Vec::with_capacity(schema.fields().len());
+    for (i, field) in schema.fields().iter().enumerate() {
+        let arrays = df.batches.iter().map(|b| b.column(i).clone()).collect();
+        columns.push(output_column(field, arrays, version)?);
+    }
+    let mut position = 0;
+    for column in &mut columns {
+        column.variable.position = position;
+        position += column.variable.length;
+    }
+    let row_len = position;
+
+    let stamp = sas_timestamp();
+    let (lib, mem, dsc, nam, obs) = match version {
+        XportVersion::V5 => ("LIBRARY", "MEMBER", "DSCRPTR", "NAMESTR", "OBS"),
+        XportVersion::V8 => ("LIBV8", "MEMBV8", "DSCPTV8", "NAMSTV8", "OBSV8"),
+    };
+    let zeros = "0".repeat(30);
+
+    // Library header
+    out.write_all(&header_record(lib, &zeros))?;
+    let mut real_header = padded("SAS", 8);
+    real_header.extend(padded("SAS", 8));
+    real_header.extend(padded("SASLIB", 8));
+    real_header.extend(padded("9.4", 8));
+    real_header.extend(padded("X64_10PR", 8));
+    real_header.extend(padded("", 24));
+    real_header.extend(padded(&stamp, 16));
+    out.write_all(&real_header)?;
+    out.write_all(&pad_record(stamp.clone().into_bytes()))?;
+
+    // Member header and descriptor
+    out.write_all(&header_record(mem, &format!("00000000000000000160000000{:04}", NAMESTR_LEN)))?;
+    out.write_all(&header_record(dsc, &zeros))?;
+    let mut member_record = padded("SAS", 8);
+    match version {
+        XportVersion::V5 => member_record.extend(padded(&member, 8)),
+        XportVersion::V8 => member_record.extend(padded(&member, 32)),
+    }
+    member_record.extend(padded("SASDATA", 8));
+    member_record.extend(padded("9.4", 8));
+    member_record.extend(padded("X64_10PR", 8));
+    if version == XportVersion::V5 {
+        member_record.extend(padded("", 24));
+    }
+    member_record.extend(padded(&stamp, 16));
+    out.write_all(&member_record)?;
+    let mut modified_record = padded(&stamp, 16);
+    modified_record.extend(padded("", 16));
+    modified_record.extend(padded("", 40));
+    modified_record.extend(padded("", 8));
+    out.write_all(&modified_record)?;
+
+    // Variable descriptors
+    out.write_all(&header_record(nam, &format!("000000{:04}00000000000000000000", columns.len())))?;
+    let mut namestrs = Vec::with_capacity(columns.len() * NAMESTR_LEN);
+    for (i, column) in columns.iter().enumerate() {
+        let v = &column.variable;
+        let short_label: String = v.label.chars().take(40).collect();
+        namestrs.extend((if v.numeric { 1u16 } else { 2u16 }).to_be_bytes());
+        namestrs.extend(0u16.to_be_bytes());
+        namestrs.extend((v.length as u16).to_be_bytes());
+        namestrs.extend(((i + 1) as u16).to_be_bytes());
+        namestrs.extend(padded(&v.name.chars().take(8).collect::<String>(), 8));
+        namestrs.extend(padded(&short_label, 40));
+        namestrs.extend(padded(&v.format, 8));
+        namestrs.extend(v.format_width.to_be_bytes());
+        namestrs.extend(v.format_decimals.to_be_bytes());
+        namestrs.extend((if v.numeric { 1u16 } else { 0u16 }).to_be_bytes());
+        namestrs.extend([0u8; 2]);
+        namestrs.extend(padded("", 8));
+        namestrs.extend(0u16.to_be_bytes());
+        namestrs.extend(0u16.to_be_bytes());
+        namestrs.extend((v.position as u32).to_be_bytes());
+        match version {
+            XportVersion::V5 => namestrs.extend([0u8; 52]),
+            XportVersion::V8 => {
+                namestrs.extend(padded(&v.name, 32));
+                namestrs.extend((v.label.len() as u16).to_be_bytes());
+                namestrs.extend([0u8; 18]);
+            }
+        }
+    }
+    out.write_all(&pad_record(namestrs))?;
+
+    // Labels that do not fit the 40-byte namestr field
+    if version == XportVersion::V8 {
+        let long_labels: Vec<(usize, &XportVariable)> = columns.iter()
+            .map(|c| &c.variable)
+            .enumerate()
+            .filter(|(_, v)| v.label.len() > 40)
+            .collect();
+        if !long_labels.is_empty() {
+            out.write_all(&header_record("LABELV8", &format!("{:05}", long_labels.len())))?;
+            let mut section = Vec::new();
+            for (i, v) in long_labels {
+                section.extend(((i + 1) as u16).to_be_bytes());
+                section.extend((v.name.len() as u16).to_be_bytes());
+                section.extend((v.label.len() as u16).to_be_bytes());
+                section.extend(v.name.as_bytes());
+                section.extend(v.label.as_bytes());
+            }
+            out.write_all(&pad_record(section))?;
+        }
+    }
+
+    // Observations
+    let n_rows: usize = df.batches.iter().map(|b| b.num_rows()).sum();
+    let obs_numbers = match version {
+        XportVersion::V5 => zeros.clone(),
+        XportVersion::V8 => format!("{:015}", n_rows),
+    };
+    out.write_all(&header_record(obs, &obs_numbers))?;
+
+    let mut written = 0usize;
+    let mut row = vec![b' '; row_len];
+    for (b, batch) in df.batches.iter().enumerate() {
+        for r in 0..batch.num_rows() {
+            for column in &columns {
+                let v = &column.variable;
+                let cell = &mut row[v.position..v.position + v.length];
+                let array = &column.batches[b];
+                if v.numeric {
+                    let values = array.as_any().downcast_ref::<Float64Array>().unwrap();
+                    let value = if values.is_null(r) { f64::NAN } else { values.value(r) };
+                    cell.copy_from_slice(&f64_to_ibm(value).with_context(|| format!("Column '{}'", v.name))?);
+                } else {
+                    let values = array.as_any().downcast_ref::<StringArray>().unwrap();
+                    cell.fill(b' ');
+                    if !values.is_null(r) {
+                        let value = values.value(r).as_bytes();
+                        cell[..value.len()].copy_from_slice(value);
+                    }
+                }
+            }
+            out.write_all(&row)?;
+            written += row_len;
+        }
+    }
+    let remainder = written % RECORD_LEN;
+    if remainder != 0 {
+        out.write_all(&vec![b' '; RECORD_LEN - remainder])?;
+    }
+    out.flush()?;
+    Ok(())
+}
@@ -0,0 +1,17 @@
+import os
+import pytest
+
+@pytest.fixture(scope="module")
+def data_dir(tmp_path_factory):
+    """A scratch directory shared by the tests of one module."""
+    return str(tmp_path_factory.mktemp("data"))
+
+@pytest.fixture
+def read_lines(data_dir):
+    """Writes a DataFrame to CSV and returns the lines of the file."""
+    def read(df):
+        path = os.path.join(data_dir, "out.csv")
+        df.to_csv(path)
+        with open(path, encoding="utf-8") as f:
+            return f.read().splitlines()
+    return read
@@ -0,0 +1,200 @@
+import grizzly
+import pytest
+import ctypes
+import io
+import os
+
+AIRLINE_SAS = os.path.join(os.path.dirname(__file__), "..", "airline.sas7bdat")
+
+CSV = b"name,age,score\nAlice,30,1.5\nBob,,-0.25\nCharlie,35,2.0\n"
+
+# Minimal bindings for the Arrow C data and stream interfaces, so the capsules
+# can be checked without pyarrow
+class ArrowSchema(ctypes.Structure):
+    pass
+
+ArrowSchema._fields_ = [
+    ("format", ctypes.c_char_p),
+    ("name", ctypes.c_char_p),
+    ("metadata", ctypes.c_void_p),
+    ("flags", ctypes.c_int64),
+    ("n_children", ctypes.c_int64),
+    ("children", ctypes.POINTER(ctypes.POINTER(ArrowSchema))),
+    ("dictionary", ctypes.c_void_p),
+    ("release", ctypes.CFUNCTYPE(None, ctypes.POINTER(ArrowSchema))),
+    ("private_data", ctypes.c_void_p),
+]
+
+class ArrowArray(ctypes.Structure):
+    pass
+
+ArrowArray._fields_ = [
+    ("length", ctypes.c_int64),
+    ("null_count", ctypes.c_int64),
+    ("offset", ctypes.c_int64),
+    ("n_buffers", ctypes.c_int64),
+    ("n_children", ctypes.c_int64),
+    ("buffers", ctypes.c_void_p),
+    ("children", ctypes.POINTER(ctypes.POINTER(ArrowArray))),
+    ("dictionary", ctypes.c_void_p),
+    ("release", ctypes.CFUNCTYPE(None, ctypes.POINTER(ArrowArray))),
+    ("private_data", ctypes.c_void_p),
+]
+
+class ArrowArrayStream(ctypes.Structure):
+    pass
+
+ArrowArrayStream._fields_ = [
+    ("get_schema", ctypes.CFUNCTYPE(ctypes.c_int, ctypes.POINTER(ArrowArrayStream), ctypes.POINTER(ArrowSchema))),
+    ("get_next", ctypes.CFUNCTYPE(ctypes.c_int, ctypes.POINTER(ArrowArrayStream), ctypes.POINTER(ArrowArray))),
+    ("get_last_error", ctypes.c_void_p),
+    ("release", ctypes.CFUNCTYPE(None, ctypes.POINTER(ArrowArrayStream))),
+    ("private_data", ctypes.c_void_p),
+]
+
+def capsule_pointer(capsule, name):
+    get_pointer = ctypes.pythonapi.PyCapsule_GetPointer
+    get_pointer.restype = ctypes.c_void_p
+    get_pointer.argtypes = [ctypes.py_object, ctypes.c_char_p]
+    return get_pointer(capsule, name)
+
+def make_capsule(struct, name):
+    new_capsule = ctypes.pythonapi.PyCapsule_New
+    new_capsule.restype = ctypes.py_object
+    new_capsule.argtypes = [ctypes.c_void_p, ctypes.c_char_p, ctypes.c_void_p]
+    return new_capsule(ctypes.addressof(struct), name, None)
+
+def children(schema):
+    return [(schema.children[i].contents.name, schema.children[i].contents.format) for i in range(schema.n_children)]
+
+def consume_stream(capsule):
+    """Moves the stream out of the capsule, as a consumer would, and reads it."""
+    stream = ArrowArrayStream.from_address(capsule_pointer(capsule, b"arrow_array_stream"))
+    schema = ArrowSchema()
+    assert stream.get_schema(ctypes.byref(stream), ctypes.byref(schema)) == 0
+    fields = children(schema)
+    schema.release(ctypes.byref(schema))
+
+    lengths = []
+    while True:
+        array = ArrowArray()
+        assert stream.get_next(ctypes.byref(stream), ctypes.byref(array)) == 0
+        if not array.release:
+            break
+        lengths.append(array.length)
+        array.release(ctypes.byref(array))
+    stream.release(ctypes.byref(stream))
+    return fields, lengths
+
+def test_arrow_c_schema():
+    df = grizzly.read_csv(CSV)
+    capsule = df.__arrow_c_schema__()
+    schema = ArrowSchema.from_address(capsule_pointer(capsule, b"arrow_schema"))
+    assert schema.format == b"+s"
+    assert children(schema) == [(b"name", b"u"), (b"age", b"l"), (b"score", b"g")]
+
+def test_arrow_c_stream():
+    df = grizzly.read_csv(CSV)
+    fields, lengths = consume_stream(df.__arrow_c_stream__())
+    assert fields == [(b"name", b"u"), (b"age", b"l"), (b"score", b"g")]
+    assert sum(lengths) == 3
+
+def test_arrow_c_stream_multiple_batches():
+    df = grizzly.read_csv(CSV)
+    df = df.concat(df).concat(df)
+    _, lengths = consume_stream(df.__arrow_c_stream__())
+    assert sum(lengths) == 9
+
+def test_arrow_c_stream_unconsumed():
+    # Dropping a capsule that was never consumed releases the stream
+    df = grizzly.read_csv(CSV)
+    for _ in range(100):
+        df.__arrow_c_stream__()
+
+def test_arrow_c_stream_requested_schema():
+    df = grizzly.read_csv(CSV)
+    # Ask for all columns as strings by passing another DataFrame's schema
+    strings = grizzly.read_csv(b"name,age,score\na,b,c\n")
+    fields, lengths = consume_stream(df.__arrow_c_stream__(strings.__arrow_c_schema__()))
+    assert fields == [(b"name", b"u"), (b"age", b"u"), (b"score", b"u")]
+    assert sum(lengths) == 3
+
+    with pytest.raises(ValueError, match="columns"):
+        df.__arrow_c_stream__(grizzly.read_csv(b"a\n1\n").__arrow_c_schema__())
+
+def test_to_arrow():
+    pa = pytest.importorskip("pyarrow")
+    table = grizzly.read_csv(CSV).to_arrow()
+    assert isinstance(table, pa.Table)
+    assert table.column_names == ["name", "age", "score"]
+    assert table.column("age").to_pylist() == [30, None, 35]
+
+def test_to_pandas():
+    pytest.importorskip("pandas")
+    pytest.importorskip("pyarrow")
+    pdf = grizzly.read_csv(CSV).to_pandas()
+    assert list(pdf.columns) == ["name", "age", "score"]
+    assert pdf["name"].tolist() == ["Alice", "Bob", "Charlie"]
+
+def csv_text(df):
+    buf = io.BytesIO()
+    df.to_csv(buf)
+    return buf.getvalue().decode()
+
+# Capsule names must outlive the capsules
+SCHEMA_NAME = b"arrow_schema"
+ARRAY_NAME = b"arrow_array"
+
+class ArrayProducer:
+    """Exposes the first batch of a DataFrame through __arrow_c_array__ only."""
+
+    def __init__(self, df, name=ARRAY_NAME):
+        stream_capsule = df.__arrow_c_stream__()
+        stream = ArrowArrayStream.from_address(capsule_pointer(stream_capsule, b"arrow_array_stream"))
+        self.schema, self.array, self.name = ArrowSchema(), ArrowArray(), name
+        assert stream.get_schema(ctypes.byref(stream), ctypes.byref(self.schema)) == 0
+        assert stream.get_next(ctypes.byref(stream), ctypes.byref(self.array)) == 0
+
+    def __arrow_c_array__(self, requested_schema=None):
+        return make_capsule(self.schema, SCHEMA_NAME), make_capsule(self.array, self.name)
+
+def test_from_arrow_stream():
+    df = grizzly.read_csv(CSV)
+    df2 = grizzly.from_arrow(df)
+    assert df2.shape == (3, 3)
+    assert csv_text(df2) == csv_text(df)
+
+def test_from_arrow_keeps_batches_and_metadata():
+    df = grizzly.read_sas(AIRLINE_SAS, chunk_size=10)
+    df2 = grizzly.from_arrow(df)
+    assert df2.shape == df.shape
+    assert df2.column_labels == df.column_labels
+    assert csv_text(df2) == csv_text(df)
+
+def test_from_arrow_array():
+    df = grizzly.read_csv(CSV)
+    df2 = grizzly.from_arrow(ArrayProducer(df))
+    assert csv_text(df2) == csv_text(df)
+
+def test_from_arrow_empty_stream():
+    buf = io.BytesIO()
+    grizzly.read_csv(CSV).to_sqlite(buf, "people")
+    df = grizzly.read_sqlite(buf.getvalue(), "SELECT * FROM people WHERE 0")
+    assert df.shape == (0, 3)
+    df2 = grizzly.from_arrow(df)
+    assert df2.shape == (0, 3)
+
+def test_from_arrow_invalid():
+    with pytest.raises(TypeError, match="__arrow_c_stream__"):
+        grizzly.from_arrow([1, 2, 3])
+    with pytest.raises(ValueError, match="arrow_array"):
+        grizzly.from_arrow(ArrayProducer(grizzly.read_csv(CSV), name=b"something_else"))
+
+def test_from_arrow_pyarrow():
+    pa = pytest.importorskip("pyarrow")
+    table = pa.table({"a": [1, 2, None], "b": ["x", "y", "z"]})
+    df = grizzly.from_arrow(table)
+    assert df.shape == (3, 2)
+    assert csv_text(df) == "a,b\n1,x\n2,y\n,z\n"
+    batch = table.to_batches()[0]
+    assert grizzly.from_arrow(batch).shape == (3, 2)
@@ -0,0 +1,110 @@
+import grizzly
+import pytest
+import os
+import json
+import zlib
+
+@pytest.fixture
+def sample_csv(data_dir):
+    path = os.path.join(data_dir, "test.csv")
+    with open(path, "w") as f:
+        f.write("name,age,score,joined\nAlice,30,1.5,2024-01-15\nBob,,-0.25,2023-12-31\nCharlie,35,,\n")
+    return path
+
+@pytest.mark.parametrize("codec", ["null", "deflate", "snappy", "zstd"])
+def test_avro_roundtrip(sample_csv, data_dir, codec, read_lines):
+    df = grizzly.read_csv(sample_csv)
+    path = os.path.join(data_dir, f"test_{codec}.avro")
+    df.to_avro(path, codec=codec)
+
+    with open(path, "rb") as f:
+        assert f.read(4) == b"Obj\x01"
+
+    df2 = grizzly.read_avro(path)
+    assert df2.shape == (3, 4)
+    assert read_lines(df2) == read_lines(df)
+
+def test_avro_empty_column(data_dir, read_lines):
+    path = os.path.join(data_dir, "empty.csv")
+    with open(path, "w") as f:
+        f.write("a,b\n1,\n2,\n")
+    df = grizzly.read_csv(path)
+    assert df.dtypes["b"] == "Null"
+
+    avro_path = os.path.join(data_dir, "empty.avro")
+    df.to_avro(avro_path)
+    df2 = grizzly.read_avro(avro_path)
+    assert df2.dtypes == df.dtypes
+    assert read_lines(df2) == ["a,b", "1,", "2,"]
+
+def test_avro_invalid_codec(sample_csv, data_dir):
+    df = grizzly.read_csv(sample_csv)
+    with pytest.raises(IOError, match="Unknown Avro codec"):
+        df.to_avro(os.path.join(data_dir, "bad.avro"), codec="lz4")
+
+def zigzag(n):
+    n = (n << 1) ^ (n >> 63)
+    out = b""
+    while True:
+        byte = n & 0x7F
+        n >>= 7
+        if n:
+            out += bytes([byte | 0x80])
+        else:
+            return out + bytes([byte])
+
+def avro_bytes(data):
+    return zigzag(len(data)) + data
+
+def write_container(path, schema, records, codec="null"):
+    """Writes an object container file from records already encoded as bytes."""
+    sync = b"0123456789abcdef"
+    meta = {"avro.schema": json.dumps(schema).encode(), "avro.codec": codec.encode()}
+    header = b"Obj\x01" + zigzag(len(meta))
+    for key, value in meta.items():
+        header += avro_bytes(key.encode()) + avro_bytes(value)
+    header += zigzag(0) + sync
+    block = b"".join(records)
+    if codec == "deflate":
+        compressor = zlib.compressobj(wbits=-15)
+        block = compressor.compress(block) + compressor.flush()
+    with open(path, "wb") as f:
+        f.write(header + zigzag(len(records)) + avro_bytes(block) + sync)
+
+LOGICAL_SCHEMA = {
+    "type": "record",
+    "name": "payment",
+    "fields": [
+        {"name": "id", "type": "long"},
+        {"name": "amount", "type": ["null", {"type": "bytes", "logicalType": "decimal", "precision": 9, "scale": 2}]},
+        {"name": "day", "type": {"type": "int", "logicalType": "date"}},
+        {"name": "at", "type": ["null", {"type": "long", "logicalType": "timestamp-millis"}]},
+    ],
+}
+
+@pytest.mark.parametrize("codec", ["null", "deflate"])
+def test_read_avro_logical_types(data_dir, codec, read_lines):
+    records = [
+        # 1234.56 is the unscaled integer 123456 = 0x01E240
+        zigzag(1) + zigzag(1) + avro_bytes(b"\x01\xe2\x40") + zigzag(19737) + zigzag(1) + zigzag(1704067200000),
+        # -0.01 is the unscaled integer -1 = 0xFF; the second branch of each union is null
+        zigzag(2) + zigzag(1) + avro_bytes(b"\xff") + zigzag(0) + zigzag(0),
+        zigzag(3) + zigzag(0) + zigzag(-1) + zigzag(0),
+    ]
+    path = os.path.join(data_dir, f"logical_{codec}.avro")
+    write_container(path, LOGICAL_SCHEMA, records, codec)
+
+    df = grizzly.read_avro(path)
+    assert df.shape == (3, 4)
+    lines = read_lines(df)
+    assert lines[0] == "id,amount,day,at"
+    assert lines[1] == "1,1234.56,2024-01-15,2024-01-01T00:00:00Z"
+    assert lines[2] == "2,-0.01,1970-01-01,"
+    assert lines[3] == "3,,1969-12-31,"
+
+def test_read_avro_unsupported_union(data_dir):
+    schema = {"type": "record", "name": "r", "fields": [{"name": "v", "type": ["int", "string"]}]}
+    path = os.path.join(data_dir, "union.avro")
+    write_container(path, schema, [zigzag(0) + zigzag(5)])
+    with pytest.raises(IOError):
+        grizzly.read_avro(path)
@@ -0,0 +1,124 @@
+import grizzly
+import pytest
+import io
+import os
+import pathlib
+import sqlite3
+
+AIRLINE_SAS = os.path.join(os.path.dirname(__file__), "..", "airline.sas7bdat")
+PEOPLE_ORC = os.path.join(os.path.dirname(__file__), "people.orc")
+
+CSV_TEXT = "name,age,score\nAlice,30,1.5\nBob,25,-0.25\nCharlie,35,2.0\n"
+
+@pytest.fixture
+def df():
+    return grizzly.read_csv(CSV_TEXT.encode())
+
+def csv_text(df):
+    buf = io.BytesIO()
+    df.to_csv(buf)
+    return buf.getvalue().decode()
+
+def test_read_csv_from_bytes_like():
+    data = CSV_TEXT.encode()
+    for source in (data, bytearray(data), memoryview(data), io.BytesIO(data), io.StringIO(CSV_TEXT)):
+        df = grizzly.read_csv(source)
+        assert df.shape == (3, 3)
+        assert csv_text(df) == CSV_TEXT
+
+def test_read_csv_from_pathlib(data_dir):
+    path = pathlib.Path(data_dir) / "test.csv"
+    path.write_text(CSV_TEXT)
+    assert grizzly.read_csv(path).shape == (3, 3)
+
+def test_read_csv_bytes_with_encoding():
+    data = "naam,stad\nRené,Köln\n".encode("latin-1")
+    df = grizzly.read_csv(data, encoding="latin1")
+    assert csv_text(df) == "naam,stad\nRené,Köln\n"
+
+def test_read_fwf_from_bytes():
+    df = grizzly.read_fwf(b"00001Alice\n00002Bob  \n", widths=[5, 5], names=["id", "name"])
+    assert csv_text(df) == "id,name\n1,Alice\n2,Bob\n"
+
+def test_parquet_buffer_roundtrip(df):
+    buf = io.BytesIO()
+    df.to_parquet(buf)
+    assert buf.getvalue()[:4] == b"PAR1"
+    assert csv_text(grizzly.read_parquet(buf.getvalue())) == CSV_TEXT
+
+def test_json_buffer_roundtrip(df):
+    buf = io.BytesIO()
+    df.to_json(buf)
+    buf.seek(0)
+    df2 = grizzly.read_json(buf)
+    assert df2.shape == (3, 3)
+    assert "30,Alice,1.5" in csv_text(df2)
+
+def test_excel_buffer_roundtrip(df):
+    buf = io.BytesIO()
+    df.to_excel(buf)
+    assert buf.getvalue()[:2] == b"PK"
+    assert grizzly.read_excel(buf.getvalue()).shape == (3, 3)
+
+    buf = io.BytesIO()
+    grizzly.write_excel(buf, {"a": df, "b": df})
+    assert grizzly.read_excel(buf.getvalue()).shape == (3, 3)
+
+def test_xpt_buffer_roundtrip(df):
+    buf = io.BytesIO()
+    df.to_xpt(buf)
+    df2 = grizzly.read_xpt(buf.getvalue())
+    assert df2.shape == (3, 3)
+
+def test_avro_buffer_roundtrip(df):
+    buf = io.BytesIO()
+    df.to_avro(buf, codec="deflate")
+    assert csv_text(grizzly.read_avro(buf.getvalue())) == CSV_TEXT
+
+def test_sqlite_buffer_roundtrip(df, data_dir):
+    buf = io.BytesIO()
+    df.to_sqlite(buf, "people")
+    df2 = grizzly.read_sqlite(buf.getvalue(), "SELECT * FROM people")
+    assert csv_text(df2) == CSV_TEXT
+
+    # The bytes are an ordinary database file
+    path = os.path.join(data_dir, "buffer.db")
+    with open(path, "wb") as f:
+        f.write(buf.getvalue())
+    conn = sqlite3.connect(path)
+    assert conn.execute("SELECT COUNT(*) FROM people").fetchone() == (3,)
+    conn.close()
+
+def test_read_sas_from_file_object():
+    with open(AIRLINE_SAS, "rb") as f:
+        data = f.read()
+    expected = grizzly.read_sas(AIRLINE_SAS)
+    with open(AIRLINE_SAS, "rb") as f:
+        assert grizzly.read_sas(f).shape == expected.shape
+    chunks = list(grizzly.iter_sas(data, chunk_size=10))
+    assert sum(c.row_count() for c in chunks) == expected.row_count()
+
+def test_read_orc_from_bytes():
+    with open(PEOPLE_ORC, "rb") as f:
+        df = grizzly.read_orc(f.read(), columns=["id"])
+    assert df.shape == (3000, 1)
+
+def test_write_to_file_object(df, data_dir):
+    path = os.path.join(data_dir, "out.csv")
+    with open(path, "wb") as f:
+        df.to_csv(f)
+    with open(path) as f:
+        assert f.read() == CSV_TEXT
+
+def test_invalid_source_and_sink(df):
+    with pytest.raises(TypeError):
+        grizzly.read_csv(42)
+    with pytest.raises(TypeError):
+        df.to_csv(42)
+
+def test_write_error_propagates(df):
+    class Broken:
+        def write(self, data):
+            raise RuntimeError("disk full")
+    with pytest.raises(IOError, match="disk full"):
+        df.to_csv(Broken())
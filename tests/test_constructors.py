@@ -0,0 +1,114 @@
+import grizzly
+import pytest
+import array
+import datetime
+import io
+
+def csv_lines(df):
+    buf = io.BytesIO()
+    df.to_csv(buf)
+    return buf.getvalue().decode().splitlines()
+
+def sqlite_types(df):
+    """Column types as seen by to_sqlite, which follows the Arrow types."""
+    import sqlite3
+    buf = io.BytesIO()
+    df.to_sqlite(buf, "t")
+    conn = sqlite3.connect(":memory:")
+    conn.deserialize(buf.getvalue())
+    types = [row[2] for row in conn.execute("PRAGMA table_info(t)")]
+    conn.close()
+    return types
+
+def test_dataframe_from_dict():
+    df = grizzly.DataFrame({"a": [1, 2, None], "b": ["x", "y", "z"], "c": [1.5, 2, None], "d": [True, False, None]})
+    assert df.shape == (3, 4)
+    assert csv_lines(df) == ["a,b,c,d", "1,x,1.5,true", "2,y,2.0,false", ",z,,"]
+
+def test_empty_dataframe():
+    assert grizzly.DataFrame().shape == (0, 0)
+    assert grizzly.DataFrame({}).shape == (0, 0)
+    assert grizzly.from_records([]).shape == (0, 0)
+
+def test_temporal_inference():
+    tz = datetime.timezone(datetime.timedelta(hours=2))
+    df = grizzly.DataFrame({
+        "day": [datetime.date(2024, 1, 15), None],
+        "at": [datetime.datetime(2024, 1, 15, 8, 30, 0, 250000), None],
+        "at_tz": [datetime.datetime(2024, 1, 15, 10, 30, tzinfo=tz), None],
+        "time": [datetime.time(13, 45, 5), None],
+    })
+    assert csv_lines(df) == [
+        "day,at,at_tz,time",
+        "2024-01-15,2024-01-15T08:30:00.250,2024-01-15T08:30:00Z,13:45:05",
+        ",,,",
+    ]
+
+def test_schema_overrides_inference():
+    df = grizzly.DataFrame({"id": [1, 2], "joined": ["2024-01-15", "2023-12-31"], "score": [1, 2]},
+                           schema={"id": "int32", "joined": "date", "score": "float64"})
+    assert sqlite_types(df) == ["INTEGER", "DATE", "REAL"]
+    assert csv_lines(df)[1] == "1,2024-01-15,1.0"
+
+def test_schema_errors():
+    with pytest.raises(ValueError, match="Unknown dtype"):
+        grizzly.DataFrame({"a": [1]}, schema={"a": "complex"})
+    with pytest.raises(ValueError, match="unknown column"):
+        grizzly.DataFrame({"a": [1]}, schema={"b": "int64"})
+    with pytest.raises(ValueError, match="Cannot convert column a"):
+        grizzly.DataFrame({"a": ["x"]}, schema={"a": "int64"})
+
+def test_invalid_data():
+    with pytest.raises(ValueError, match="Column b has 1 values, expected 2"):
+        grizzly.DataFrame({"a": [1, 2], "b": [1]})
+    with pytest.raises(TypeError, match="mixes"):
+        grizzly.DataFrame({"a": [1, "x"]})
+    with pytest.raises(TypeError, match="Unsupported value type"):
+        grizzly.DataFrame({"a": [object()]})
+
+def test_from_records():
+    df = grizzly.from_records([
+        {"name": "Alice", "age": 30},
+        {"name": "Bob"},
+        {"age": 35, "city": "Utrecht"},
+    ])
+    assert csv_lines(df) == ["name,age,city", "Alice,30,", "Bob,,", ",35,Utrecht"]
+
+def test_from_records_schema():
+    df = grizzly.from_records([{"a": 1}, {"a": 2}], schema={"a": "str"})
+    assert sqlite_types(df) == ["TEXT"]
+    with pytest.raises(TypeError, match="Record 1 is not a dict"):
+        grizzly.from_records([{"a": 1}, [1]])
+
+def test_from_numpy_buffers():
+    df = grizzly.from_numpy(array.array("d", [1.5, 2.5, 3.5]))
+    assert csv_lines(df) == ["column_1", "1.5", "2.5", "3.5"]
+
+    matrix = memoryview(array.array("q", [1, 2, 3, 4, 5, 6])).cast("B").cast("q", [3, 2])
+    df = grizzly.from_numpy(matrix, columns=["x", "y"])
+    assert csv_lines(df) == ["x,y", "1,2", "3,4", "5,6"]
+
+    df = grizzly.from_numpy(matrix, schema={"column_2": "float64"})
+    assert csv_lines(df)[1] == "1,2.0"
+
+def test_from_numpy_lists():
+    df = grizzly.from_numpy([[1, "a"], [2, "b"]], columns=["n", "s"])
+    assert csv_lines(df) == ["n,s", "1,a", "2,b"]
+    with pytest.raises(ValueError, match="column names"):
+        grizzly.from_numpy([[1, 2]], columns=["only"])
+    # Errors name columns the way the generated names spell them
+    with pytest.raises(TypeError, match="column_2"):
+        grizzly.from_numpy([[1, "a"], [2, 3]])
+
+def test_from_numpy_numpy():
+    np = pytest.importorskip("numpy")
+    df = grizzly.from_numpy(np.arange(6, dtype=np.int64).reshape(3, 2), columns=["a", "b"])
+    assert csv_lines(df) == ["a,b", "0,1", "2,3", "4,5"]
+    # Strided views are read in logical order
+    df = grizzly.from_numpy(np.arange(6, dtype=np.float32).reshape(2, 3).T)
+    assert csv_lines(df)[1] == "0.0,3.0"
+    df = grizzly.from_numpy(np.array([True, False]))
+    assert csv_lines(df) == ["column_1", "true", "false"]
+    structured = np.array([(1, 2.5), (2, 3.5)], dtype=[("id", "i8"), ("value", "f8")])
+    assert csv_lines(grizzly.from_numpy(structured)) == ["id,value", "1,2.5", "2,3.5"]
+    assert csv_lines(grizzly.DataFrame({"a": np.array([1, 2])})) == ["a", "1", "2"]
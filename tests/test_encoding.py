@@ -0,0 +1,68 @@
+import grizzly
+import pytest
+import os
+
+def write_bytes(data_dir, name, data):
+    path = os.path.join(data_dir, name)
+    with open(path, "wb") as f:
+        f.write(data)
+    return path
+
+CSV_TEXT = "naam,stad,bedrag\nRené,Köln,1.5\nFrançoise,Zürich,2.25\n"
+
+def test_read_csv_latin1(data_dir, read_lines):
+    path = write_bytes(data_dir, "latin1.csv", CSV_TEXT.encode("latin-1"))
+    df = grizzly.read_csv(path, encoding="latin1")
+    assert df.shape == (2, 3)
+    assert read_lines(df) == CSV_TEXT.splitlines()
+
+def test_read_csv_windows_1252(data_dir, read_lines):
+    # 0x80 is the euro sign in Windows-1252
+    text = "item,price\nkoffie €,2.5\n"
+    path = write_bytes(data_dir, "cp1252.csv", text.encode("cp1252"))
+    df = grizzly.read_csv(path, encoding="windows-1252")
+    assert read_lines(df) == text.splitlines()
+
+def test_read_csv_utf16(data_dir, read_lines):
+    path = write_bytes(data_dir, "utf16.csv", CSV_TEXT.encode("utf-16-le"))
+    df = grizzly.read_csv(path, encoding="utf-16le")
+    assert read_lines(df) == CSV_TEXT.splitlines()
+
+def test_read_csv_invalid_utf8_without_encoding(data_dir):
+    path = write_bytes(data_dir, "invalid.csv", CSV_TEXT.encode("latin-1"))
+    with pytest.raises(IOError):
+        grizzly.read_csv(path)
+
+def test_read_csv_strips_bom(data_dir, read_lines):
+    path = write_bytes(data_dir, "bom.csv", b"\xef\xbb\xbf" + CSV_TEXT.encode("utf-8"))
+    for encoding in (None, "utf-8"):
+        df = grizzly.read_csv(path, encoding=encoding)
+        assert read_lines(df)[0] == "naam,stad,bedrag"
+
+def test_read_csv_large_latin1(data_dir, read_lines):
+    n = 50000
+    rows = "".join(f"{i},Straße {i}\n" for i in range(n))
+    path = write_bytes(data_dir, "large.csv", ("id,adres\n" + rows).encode("latin-1"))
+    assert os.path.getsize(path) > 1024 * 1024 // 2
+    df = grizzly.read_csv(path, encoding="latin1")
+    assert df.shape == (n, 2)
+    assert read_lines(df)[n] == f"{n - 1},Straße {n - 1}"
+
+def test_read_json_encoding(data_dir, read_lines):
+    text = '{"naam": "René", "stad": "Köln"}\n{"naam": "Søren", "stad": "Århus"}\n'
+    path = write_bytes(data_dir, "latin1.json", text.encode("latin-1"))
+    df = grizzly.read_json(path, encoding="iso-8859-1")
+    assert df.shape == (2, 2)
+    assert read_lines(df) == ["naam,stad", "René,Köln", "Søren,Århus"]
+
+def test_read_json_strips_bom(data_dir, read_lines):
+    path = write_bytes(data_dir, "bom.json", b"\xef\xbb\xbf" + b'{"a": 1}\n{"a": 2}\n')
+    df = grizzly.read_json(path)
+    assert read_lines(df) == ["a", "1", "2"]
+
+def test_unknown_encoding(data_dir):
+    path = write_bytes(data_dir, "plain.csv", b"a\n1\n")
+    with pytest.raises(ValueError, match="Unknown encoding"):
+        grizzly.read_csv(path, encoding="klingon")
+    with pytest.raises(ValueError, match="Unknown encoding"):
+        grizzly.read_json(path, encoding="klingon")
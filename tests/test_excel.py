@@ -0,0 +1,96 @@
+import grizzly
+import pytest
+import os
+import zipfile
+
+@pytest.fixture
+def sample_csv(data_dir):
+    path = os.path.join(data_dir, "test.csv")
+    with open(path, "w") as f:
+        f.write("name,age,city\nAlice,30,New York\nBob,25,Los Angeles\nCharlie,35,Chicago\n")
+    return path
+
+def test_write_excel_multiple_sheets(sample_csv, data_dir):
+    df = grizzly.read_csv(sample_csv)
+    summary = df.groupby_sum("city", "age")
+    path = os.path.join(data_dir, "report.xlsx")
+    grizzly.write_excel(path, {"Summary": summary, "Detail": df})
+
+    with zipfile.ZipFile(path) as z:
+        workbook = z.read("xl/workbook.xml").decode()
+    assert 'name="Summary"' in workbook
+    assert 'name="Detail"' in workbook
+
+    # read_excel reads the first sheet
+    df2 = grizzly.read_excel(path)
+    assert df2.shape == (3, 2)
+
+def test_write_excel_formatting(sample_csv, data_dir):
+    df = grizzly.read_csv(sample_csv)
+    path = os.path.join(data_dir, "formatted.xlsx")
+    grizzly.write_excel(
+        path,
+        {"Plain": df, "Styled": df},
+        bold_header=True,
+        freeze_panes=(1, 0),
+        autofilter=True,
+        autofit=True,
+        sheet_options={"Styled": {"table_style": "Medium9"}},
+    )
+
+    with zipfile.ZipFile(path) as z:
+        names = z.namelist()
+        plain = z.read("xl/worksheets/sheet1.xml").decode()
+        tables = [n for n in names if n.startswith("xl/tables/")]
+        table_xml = z.read(tables[0]).decode()
+    assert "<autoFilter" in plain
+    assert "<pane" in plain
+    assert len(tables) == 1
+    assert "TableStyleMedium9" in table_xml
+
+    df2 = grizzly.read_excel(path)
+    assert df2.row_count() == 3
+
+def test_write_excel_invalid_options(sample_csv, data_dir):
+    df = grizzly.read_csv(sample_csv)
+    path = os.path.join(data_dir, "invalid.xlsx")
+    with pytest.raises(Exception):
+        grizzly.write_excel(path, {"Sheet": df}, table_style="NotAStyle")
+    with pytest.raises(ValueError):
+        grizzly.write_excel(path, {"Sheet": df}, sheet_options={"Sheet": {"colour": "red"}})
+
+def test_write_excel_typed_cells(data_dir):
+    import datetime
+    df = grizzly.DataFrame({
+        "name": ["Alice", None],
+        "age": [30, 25],
+        "score": [1.5, None],
+        "active": [True, False],
+        "joined": [datetime.date(2024, 1, 15), datetime.date(1850, 6, 1)],
+        "seen": [datetime.datetime(2024, 1, 15, 8, 30), None],
+    })
+    path = os.path.join(data_dir, "typed.xlsx")
+    grizzly.write_excel(path, {"Sheet": df}, autofilter=True)
+
+    with zipfile.ZipFile(path) as z:
+        sheet = z.read("xl/worksheets/sheet1.xml").decode()
+    # Numbers, booleans and dates are stored as values, not shared strings
+    assert '<c r="B2"><v>30</v></c>' in sheet
+    assert '<c r="C2"><v>1.5</v></c>' in sheet
+    assert '<c r="D2" t="b"><v>1</v></c>' in sheet
+    assert '<c r="E2" s="1"><v>45306</v></c>' in sheet
+    assert '<c r="F2" s="2"><v>45306.35416666667</v></c>' in sheet
+    assert '<c r="A2" t="s">' in sheet
+    # Excel has no dates before 1900, so those are written as text
+    assert '<c r="E3" t="s">' in sheet
+    assert '<c r="A3"' not in sheet and '<c r="C3"' not in sheet
+
+def test_write_excel_no_rows(sample_csv, data_dir):
+    df = grizzly.read_csv(sample_csv).query("age > 100")
+    path = os.path.join(data_dir, "empty.xlsx")
+    grizzly.write_excel(path, {"Sheet": df}, table_style="Medium9")
+
+    with zipfile.ZipFile(path) as z:
+        strings = z.read("xl/sharedStrings.xml").decode()
+    for name in ["name", "age", "city"]:
+        assert f"<t>{name}</t>" in strings
@@ -0,0 +1,114 @@
+import grizzly
+import pytest
+import datetime
+from grizzly import col, lit, when
+
+@pytest.fixture
+def df():
+    df = grizzly.DataFrame({
+        "price": [2.5, 10.0, None, 4.0],
+        "qty": [2, 1, 5, 3],
+        "name": ["apple", "melon", "kiwi", None],
+        "day": [datetime.date(2024, 1, 15), datetime.date(2024, 2, 1), None, datetime.date(2023, 12, 31)],
+    })
+    return df.concat(df.head(1))
+
+def test_arithmetic(df):
+    out = df.with_columns(total=col("price") * col("qty"), more=col("qty") + 1, half=col("qty") / 2)
+    assert out.columns == ["price", "qty", "name", "day", "total", "more", "half"]
+    assert out["total"].to_list() == [5.0, 10.0, None, 12.0, 5.0]
+    assert out["more"].to_list() == [3, 2, 6, 4, 3]
+    assert out.dtypes["more"] == "int64"
+    assert out["half"].to_list() == [1.0, 0.5, 2.5, 1.5, 1.0]
+    out = df.with_columns(r=10 - col("qty"), m=col("qty") % 2, n=-col("qty"))
+    assert out["r"].to_list() == [8, 9, 5, 7, 8]
+    assert out["m"].to_list() == [0, 1, 1, 1, 0]
+    assert out["n"].to_list() == [-2, -1, -5, -3, -2]
+
+def test_narrow_arithmetic_promotes():
+    df = grizzly.DataFrame({"u8": [10, 200, 0], "i32": [5, 3_000_000, -7]}, schema={"u8": "uint8", "i32": "int32"})
+    out = df.with_columns(plus=col("u8") + 250, times=col("i32") * 1000, minus=col("u8") - 1)
+    assert out["plus"].to_list() == [260, 450, 250]
+    assert out["times"].to_list() == [5000, 3_000_000_000, -7000]
+    assert out["minus"].to_list() == [9, 199, -1]
+    assert out.dtypes["plus"] == "int64"
+    # Comparisons still run in the column's type
+    assert df.with_columns(big=col("u8") > 5)["big"].to_list() == [True, True, False]
+
+def test_comparisons_and_logic(df):
+    out = df.with_columns(
+        flag=col("qty") > 2,
+        both=(col("qty") >= 2) & (col("price") < 5),
+        either=(col("qty") == 1) | col("price").is_null(),
+        neither=~(col("qty") != 2),
+    )
+    assert out["flag"].to_list() == [False, False, True, True, False]
+    # Kleene logic: False & null is False, True & null is null
+    assert out["both"].to_list() == [True, False, None, True, True]
+    assert out["either"].to_list() == [False, True, True, False, False]
+    assert out["neither"].to_list() == [True, False, False, False, True]
+
+def test_string_and_date_comparisons(df):
+    out = df.with_columns(is_kiwi=col("name") == "kiwi", recent=col("day") >= "2024-01-01")
+    assert out["is_kiwi"].to_list() == [False, False, True, None, False]
+    assert out["recent"].to_list() == [True, True, None, False, True]
+    out = df.with_columns(recent=col("day") > datetime.date(2024, 1, 20))
+    assert out["recent"].to_list() == [False, True, None, False, False]
+
+def test_literals_cast_and_alias(df):
+    out = df.with_columns(lit(1).alias("one"), col("qty").cast("float64").alias("qty_f"), source=lit("shop"))
+    assert out["one"].to_list() == [1] * 5
+    assert out.dtypes["qty_f"] == "float64"
+    assert out["source"].to_list() == ["shop"] * 5
+    # Without an alias, a column keeps the name of its leftmost column and replaces it
+    out = df.with_columns(col("qty") * 10)
+    assert out.columns == ["price", "qty", "name", "day"]
+    assert out["qty"].to_list() == [20, 10, 50, 30, 20]
+
+def test_duplicate_output_names(df):
+    with pytest.raises(ValueError, match="same column: qty"):
+        df.with_columns(col("qty") * 2, col("qty") + 1)
+    with pytest.raises(ValueError, match="same column: total"):
+        df.with_columns((col("qty") * 2).alias("total"), total=col("price"))
+
+def test_cast_strictness():
+    df = grizzly.DataFrame({"s": ["1", "x"]})
+    with pytest.raises(ValueError):
+        df.with_columns(n=col("s").cast("int64"))
+    assert df.with_columns(n=col("s").cast("int64", strict=False))["n"].to_list() == [1, None]
+    with pytest.raises(ValueError, match="Unknown dtype"):
+        col("s").cast("complex")
+
+def test_when_then_otherwise(df):
+    size = when(col("qty") >= 3).then(lit("large")).when(col("qty") == 2).then(lit("medium")).otherwise(lit("small"))
+    out = df.with_columns(size=size)
+    assert out["size"].to_list() == ["medium", "small", "large", "large", "medium"]
+    # Without otherwise, unmatched rows are null; numeric branches meet at a common type
+    out = df.with_columns(bonus=when(col("price") > 3).then(col("qty")).when(col("qty") > 4).then(0.5))
+    assert out["bonus"].to_list() == [None, 1.0, 0.5, 3.0, None]
+    with pytest.raises(TypeError, match="incompatible types"):
+        df.with_columns(bad=when(col("qty") > 1).then(1).otherwise("x"))
+
+def test_filter(df):
+    out = df.filter((col("qty") > 1) & (col("price") < 5))
+    assert out.rows() == [(2.5, 2, "apple", datetime.date(2024, 1, 15)), (4.0, 3, None, datetime.date(2023, 12, 31)),
+                          (2.5, 2, "apple", datetime.date(2024, 1, 15))]
+    with pytest.raises(TypeError, match="must be boolean"):
+        df.filter(col("qty"))
+
+def test_errors(df):
+    with pytest.raises(ValueError, match="Unknown column: nope"):
+        df.with_columns(x=col("nope") + 1)
+    with pytest.raises(ValueError):
+        df.with_columns(x=col("name") + 1)
+    with pytest.raises(TypeError, match="truth value"):
+        if col("qty") > 1:
+            pass
+
+def test_repr():
+    expr = when((col("a") + 1) > 2).then(lit("x")).otherwise(None).alias("b")
+    assert repr(expr) == 'when(((col("a") + 1) > 2)).then(\'x\').otherwise(None).alias("b")'
+
+def test_empty_dataframe():
+    out = grizzly.DataFrame().with_columns(a=lit(1))
+    assert out.shape == (0, 1)
@@ -0,0 +1,71 @@
+import grizzly
+import pytest
+import os
+
+@pytest.fixture
+def sample_fwf(data_dir):
+    path = os.path.join(data_dir, "test.txt")
+    with open(path, "w", encoding="utf-8") as f:
+        f.write("00001Alice     30   1.50 2024-01-15\n")
+        f.write("00002Bob            -0.25 2023-12-31\r\n")
+        f.write("00003Zoë       35         \n")
+    return path
+
+def test_read_fwf_widths(sample_fwf, read_lines):
+    df = grizzly.read_fwf(sample_fwf, widths=[5, 10, 3, 7, 11], names=["id", "name", "age", "score", "joined"])
+    assert df.shape == (3, 5)
+
+    lines = read_lines(df)
+    assert lines[0] == "id,name,age,score,joined"
+    assert lines[1] == "1,Alice,30,1.5,2024-01-15"
+    assert lines[2] == "2,Bob,,-0.25,2023-12-31"
+    # Positions count characters, so the multi-byte name doesn't shift later fields
+    assert lines[3] == "3,Zoë,35,,"
+
+def test_read_fwf_colspecs_and_dtypes(sample_fwf, read_lines):
+    df = grizzly.read_fwf(sample_fwf, colspecs=[(5, 15), (0, 5)], names=["name", "id"], dtypes={"id": "str"})
+    lines = read_lines(df)
+    assert lines[0] == "name,id"
+    assert lines[1] == "Alice,00001"
+
+def test_read_fwf_default_names(sample_fwf):
+    df = grizzly.read_fwf(sample_fwf, widths=[5, 10])
+    assert df.shape == (3, 2)
+
+def test_read_fwf_parallel(data_dir, read_lines):
+    path = os.path.join(data_dir, "large.txt")
+    n = 40000
+    with open(path, "w") as f:
+        for i in range(n):
+            f.write(f"{i:08d}{'name' + str(i):<20}{i * 0.5:>12.2f}\n")
+    assert os.path.getsize(path) > 1024 * 1024
+
+    df = grizzly.read_fwf(path, widths=[8, 20, 12], names=["id", "name", "value"])
+    assert df.shape == (n, 3)
+    lines = read_lines(df)
+    assert lines[1] == "0,name0,0.0"
+    assert lines[n] == f"{n - 1},name{n - 1},{(n - 1) * 0.5}"
+
+def test_read_fwf_invalid_arguments(sample_fwf):
+    with pytest.raises(ValueError):
+        grizzly.read_fwf(sample_fwf)
+    with pytest.raises(ValueError):
+        grizzly.read_fwf(sample_fwf, widths=[5], colspecs=[(0, 5)])
+    with pytest.raises(ValueError, match="Unknown dtype"):
+        grizzly.read_fwf(sample_fwf, widths=[5], dtypes={"column_1": "complex"})
+    with pytest.raises(IOError, match="names"):
+        grizzly.read_fwf(sample_fwf, widths=[5, 10], names=["id"])
+
+def test_read_fwf_bad_value(sample_fwf):
+    with pytest.raises(IOError):
+        grizzly.read_fwf(sample_fwf, widths=[5, 10], dtypes={"column_2": "int64"})
+
+def test_read_fwf_cp1252(data_dir, read_lines):
+    path = os.path.join(data_dir, "cp1252.txt")
+    with open(path, "wb") as f:
+        f.write("Café      1 €5  \r\nNaïve     2 €12 \r\n".encode("cp1252"))
+    df = grizzly.read_fwf(path, widths=[10, 2, 4], names=["name", "n", "price"], encoding="cp1252")
+    assert df.dtypes == {"name": "str", "n": "int64", "price": "str"}
+    assert read_lines(df) == ["name,n,price", "Café,1,€5", "Naïve,2,€12"]
+    with pytest.raises(ValueError, match="Unknown encoding"):
+        grizzly.read_fwf(path, widths=[10], encoding="klingon")
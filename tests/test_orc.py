@@ -0,0 +1,38 @@
+import grizzly
+import pytest
+import os
+
+# 3000 rows (id, name, score) written as three stripes of 1000 rows;
+# every seventh name is null. Regenerate with `cargo run --example people_orc`.
+PEOPLE_ORC = os.path.join(os.path.dirname(__file__), "people.orc")
+
+def test_read_orc():
+    df = grizzly.read_orc(PEOPLE_ORC)
+    assert df.shape == (3000, 3)
+
+def test_read_orc_values(read_lines):
+    lines = read_lines(grizzly.read_orc(PEOPLE_ORC))
+    assert lines[0] == "id,name,score"
+    assert lines[1] == "0,,0.0"
+    assert lines[2] == "1,row1,0.25"
+    assert lines[3000] == "2999,row2999,749.75"
+
+def test_read_orc_columns(read_lines):
+    df = grizzly.read_orc(PEOPLE_ORC, columns=["score", "id"])
+    assert df.shape == (3000, 2)
+
+    # Columns come back in the requested order
+    lines = read_lines(df)
+    assert lines[0] == "score,id"
+    assert lines[2] == "0.25,1"
+
+def test_read_orc_unknown_column():
+    with pytest.raises(IOError, match="Column not found"):
+        grizzly.read_orc(PEOPLE_ORC, columns=["missing"])
+
+def test_read_orc_invalid(data_dir):
+    path = os.path.join(data_dir, "bad.orc")
+    with open(path, "w") as f:
+        f.write("not an orc file")
+    with pytest.raises(IOError):
+        grizzly.read_orc(path)
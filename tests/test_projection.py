@@ -0,0 +1,49 @@
+import grizzly
+import pytest
+
+@pytest.fixture
+def df():
+    df = grizzly.DataFrame({"id": [1, 2], "name": ["Alice", "Bob"], "score": [1.5, 2.5]})
+    return df.concat(df)
+
+def test_select(df):
+    sub = df.select(["score", "id"])
+    assert sub.columns == ["score", "id"]
+    assert sub.rows() == [(1.5, 1), (2.5, 2), (1.5, 1), (2.5, 2)]
+    assert df[["name"]].to_dict() == {"name": ["Alice", "Bob", "Alice", "Bob"]}
+    assert df.select([]).shape == (4, 0)
+
+def test_drop(df):
+    assert df.drop(["name"]).columns == ["id", "score"]
+    assert df.drop(["name"]).shape == (4, 2)
+    assert df.drop([]).columns == ["id", "name", "score"]
+
+def test_reorder(df):
+    assert df.reorder(["score"]).columns == ["score", "id", "name"]
+    assert df.reorder(["name", "id"]).rows()[0] == ("Alice", 1, 1.5)
+
+def test_rename(df):
+    renamed = df.rename({"id": "key", "score": "points"})
+    assert renamed.columns == ["key", "name", "points"]
+    assert renamed.to_dict()["points"] == [1.5, 2.5, 1.5, 2.5]
+    # Swapping names is fine as long as the result has no duplicates
+    assert df.rename({"id": "name", "name": "id"}).columns == ["name", "id", "score"]
+    assert df.columns == ["id", "name", "score"]
+
+def test_rename_after_join():
+    left = grizzly.DataFrame({"id": [1, 2], "value": [10, 20]})
+    right = grizzly.DataFrame({"id": [1, 2], "value": [100, 200]})
+    joined = left.join(right, on="id").rename({"value_right": "other"})
+    assert joined.columns == ["id", "value", "other"]
+
+def test_errors(df):
+    with pytest.raises(ValueError, match="missing"):
+        df.select(["missing"])
+    with pytest.raises(ValueError, match="missing"):
+        df.drop(["missing"])
+    with pytest.raises(ValueError, match="missing"):
+        df.rename({"missing": "x"})
+    with pytest.raises(ValueError, match="listed twice"):
+        df.select(["id", "id"])
+    with pytest.raises(ValueError, match="duplicate column: name"):
+        df.rename({"id": "name"})
@@ -0,0 +1,153 @@
+import grizzly
+import pytest
+
+@pytest.fixture
+def df():
+    return grizzly.DataFrame({
+        "name": ["Alice", "Bob", "Carol", None, "Eve"],
+        "price": [5.0, 12.5, 30.0, 8.0, None],
+        "qty": [1, 3, 7, 5, 2],
+        "region": ["EU", "US", "APAC", "EU", "US"],
+        "active": [True, False, True, None, True],
+    })
+
+def names(df):
+    return [row[0] for row in df.rows()]
+
+def test_and_or_parentheses(df):
+    assert names(df.query("(price > 10 and region in ('EU','US')) or name is null")) == ["Bob", None]
+    assert names(df.query("price > 10 and region == 'US' or qty == 1")) == ["Alice", "Bob"]
+    assert names(df.query("price > 10 AND (region = 'US' OR qty = 7)")) == ["Bob", "Carol"]
+
+def test_not(df):
+    assert names(df.query("not active")) == ["Bob"]
+    assert names(df.query("not (qty > 2)")) == ["Alice", "Eve"]
+    assert names(df.query("!active")) == ["Bob"]
+
+def test_in(df):
+    assert names(df.query("region in ('EU', 'APAC')")) == ["Alice", "Carol", None]
+    assert names(df.query("region not in ('EU', 'APAC')")) == ["Bob", "Eve"]
+    assert names(df.query("qty in (1, 2)")) == ["Alice", "Eve"]
+
+def test_between(df):
+    assert names(df.query("qty between 2 and 5")) == ["Bob", None, "Eve"]
+    assert names(df.query("qty not between 2 and 5 and price < 10")) == ["Alice"]
+    assert names(df.query("price between 5 and 10.5")) == ["Alice", None]
+
+def test_null_checks(df):
+    assert names(df.query("name is null")) == [None]
+    assert names(df.query("price is not null and active is null")) == [None]
+
+def test_arithmetic_in_predicates(df):
+    assert names(df.query("price * qty > 40")) == ["Carol"]
+    assert names(df.query("qty % 2 == 0")) == ["Eve"]
+    assert names(df.query("qty > -1 and price < 2 * 5")) == ["Alice", None]
+
+def test_bare_words_and_columns(df):
+    # An unknown bare word on the right of a comparison is a string
+    assert names(df.query("name == Alice")) == ["Alice"]
+    assert names(df.query("region != EU")) == ["Bob", "Carol", "Eve"]
+    df = df.with_columns(other=grizzly.col("name"))
+    assert len(df.query("name == other").rows()) == 4
+
+def test_syntax_errors(df):
+    with pytest.raises(ValueError, match="position 6: expected a value or column"):
+        df.query("qty > ")
+    with pytest.raises(ValueError, match="unexpected"):
+        df.query("qty > 1 qty")
+    with pytest.raises(ValueError, match="expected \\)"):
+        df.query("(qty > 1")
+    with pytest.raises(ValueError, match="unterminated string"):
+        df.query("name == 'Bob")
+    with pytest.raises(ValueError, match="expected null"):
+        df.query("name is 1")
+    with pytest.raises(ValueError, match="Unknown column: missing"):
+        df.query("missing > 1")
+    with pytest.raises(TypeError, match="must be boolean"):
+        df.query("qty + 1")
+
+def test_expr_is_in_and_between(df):
+    col = grizzly.col
+    assert names(df.filter(col("region").is_in(["EU", "US"]) & col("qty").is_between(2, 5))) == ["Bob", None, "Eve"]
+    assert names(df.filter(col("qty").is_in([]))) == []
+
+def test_no_matching_rows(df):
+    for q in ["qty > 100", "qty == 99999999999999999999"]:
+        out = df.query(q)
+        assert out.shape == (0, 5)
+        assert out.columns == ["name", "price", "qty", "region", "active"]
+    assert df.query("qty > 100").select(["qty"]).columns == ["qty"]
+
+def test_quoted_strings_and_backticks():
+    df = grizzly.DataFrame({"city": ["New York", "O'Hare", 'Say "hi"'], "unit price": [1.5, 2.5, 3.5]})
+    assert df.query("city == 'New York'").shape == (1, 2)
+    assert df.query('city == "New York"').shape == (1, 2)
+    assert df.query("city == 'O''Hare'").shape == (1, 2)
+    assert df.query("city == 'O\\'Hare'").shape == (1, 2)
+    assert df.query("city == 'Say \"hi\"'").shape == (1, 2)
+    assert [r[0] for r in df.query("`unit price` > 2").rows()] == ["O'Hare", 'Say "hi"']
+    with pytest.raises(ValueError, match="Unknown column: unit"):
+        df.query("`unit` > 2")
+
+def test_typed_columns():
+    import datetime
+    df = grizzly.DataFrame(
+        {"i32": [1, 2, 3], "u8": [10, 200, 255], "f32": [0.5, 1.5, 2.5], "flag": [True, False, True],
+         "day": ["2024-01-15", "2024-02-01", "2023-12-31"],
+         "at": ["2024-01-15 08:30:00", "2024-02-01 00:00:00", "2023-12-31 23:59:59"]},
+        schema={"i32": "int32", "u8": "uint8", "f32": "float32", "day": "date", "at": "datetime"},
+    )
+    def ids(q):
+        return df.query(q).to_dict()["i32"]
+    assert ids("i32 >= 2") == [2, 3]
+    assert ids("u8 == 255 or u8 < 100") == [1, 3]
+    assert ids("u8 > -1") == [1, 2, 3]
+    assert ids("f32 between 1 and 2.5") == [2, 3]
+    assert ids("flag") == [1, 3]
+    assert ids("flag == false") == [2]
+    assert ids("day > date '2024-01-01'") == [1, 2]
+    assert ids("day < '2024-01-01'") == [3]
+    assert ids("at >= timestamp '2024-01-15 08:30:00'") == [1, 2]
+    assert ids("at > date '2024-01-31'") == [2]
+    assert ids("at < timestamp '2024-01-01T00:00:00Z'") == [3]
+    assert ids("day in (date '2024-02-01', date '2023-12-31')") == [2, 3]
+
+    tz = datetime.timezone(datetime.timedelta(hours=2))
+    aware = grizzly.DataFrame({"at": [datetime.datetime(2024, 1, 15, 10, 30, tzinfo=tz)]})
+    assert aware.query("at == timestamp '2024-01-15 08:30:00Z'").shape == (1, 1)
+    assert aware.query("at == timestamp '2024-01-15T10:30:00+02:00'").shape == (1, 1)
+
+    times = grizzly.DataFrame({"t": [datetime.time(9, 0), datetime.time(14, 30)]})
+    assert times.query("t > time '12:00'").shape == (1, 1)
+
+    with pytest.raises(ValueError, match="invalid date literal"):
+        df.query("day > date '2024-13-01'")
+
+def zigzag(n):
+    n = (n << 1) ^ (n >> 63)
+    out = b""
+    while True:
+        byte = n & 0x7F
+        n >>= 7
+        if n:
+            out += bytes([byte | 0x80])
+        else:
+            return out + bytes([byte])
+
+def test_decimal_column():
+    import json
+    schema = {"type": "record", "name": "r", "fields": [
+        {"name": "amount", "type": {"type": "bytes", "logicalType": "decimal", "precision": 9, "scale": 2}}]}
+    meta = {"avro.schema": json.dumps(schema).encode(), "avro.codec": b"null"}
+    header = b"Obj\x01" + zigzag(len(meta))
+    for key, value in meta.items():
+        header += zigzag(len(key)) + key.encode() + zigzag(len(value)) + value
+    # 1234.56, -0.01 and 10.00 as unscaled big-endian integers
+    values = [b"\x01\xe2\x40", b"\xff", b"\x03\xe8"]
+    block = b"".join(zigzag(len(v)) + v for v in values)
+    sync = b"0123456789abcdef"
+    df = grizzly.read_avro(header + zigzag(0) + sync + zigzag(3) + zigzag(len(block)) + block + sync)
+
+    assert df.query("amount > 10").shape == (1, 1)
+    assert df.query("amount >= 10").shape == (2, 1)
+    assert df.query("amount < 0.5").shape == (1, 1)
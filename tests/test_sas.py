@@ -2,6 +2,8 @@ import grizzly
 import pytest
 import os
 import shutil
+import struct
+import datetime
 
 @pytest.fixture(scope="module")
 def data_dir():
@@ -40,3 +42,189 @@ def test_sas_integration(data_dir):
     
     print("SAS7BDAT read functionality is available and ready to use.")
     print("To fully test, provide a .sas7bdat file and update this test.")
+
+AIRLINE_SAS = os.path.join(os.path.dirname(__file__), "..", "airline.sas7bdat")
+
+def test_sas_column_names_and_labels():
+    df = grizzly.read_sas(AIRLINE_SAS)
+    assert df.shape == (32, 6)
+    labels = df.column_labels
+    assert list(labels.keys()) == ["YEAR", "Y", "W", "R", "L", "K"]
+    assert labels["YEAR"] == "year"
+    assert labels["W"] == "wage rate"
+
+def test_sas_chunked_read():
+    df = grizzly.read_sas(AIRLINE_SAS, chunk_size=10)
+    assert df.shape == (32, 6)
+
+    chunks = list(grizzly.iter_sas(AIRLINE_SAS, chunk_size=10))
+    assert [c.row_count() for c in chunks] == [10, 10, 10, 2]
+    assert all(c.column_count() == 6 for c in chunks)
+
+    with pytest.raises(Exception):
+        grizzly.iter_sas(AIRLINE_SAS, chunk_size=0)
+
+def test_sas_projection_and_row_window():
+    df = grizzly.read_sas(AIRLINE_SAS, columns=["W", "YEAR"])
+    assert df.shape == (32, 2)
+    assert list(df.column_labels.keys()) == ["W", "YEAR"]
+
+    df = grizzly.read_sas(AIRLINE_SAS, n_rows=5)
+    assert df.shape == (5, 6)
+
+    df = grizzly.read_sas(AIRLINE_SAS, skip_rows=30, columns=["YEAR"], chunk_size=1)
+    assert df.shape == (2, 1)
+
+    df = grizzly.read_sas(AIRLINE_SAS, skip_rows=100)
+    assert df.shape == (0, 6)
+
+    chunks = list(grizzly.iter_sas(AIRLINE_SAS, chunk_size=4, n_rows=10, skip_rows=3))
+    assert [c.row_count() for c in chunks] == [4, 4, 2]
+
+    with pytest.raises(Exception):
+        grizzly.read_sas(AIRLINE_SAS, columns=["NOPE"])
+
+SAS_MAGIC = bytes(12) + bytes.fromhex("c2ea8160b31411cfbd92080009c7318c181f1011")
+SAS_PAGE_SIZE = 4096
+SAS_MISSING = struct.pack("<Q", 0xFFFFFE0000000000)
+
+SAS_EPOCH = datetime.datetime(1960, 1, 1)
+
+def sas_value(fmt, value):
+    if value is None:
+        return SAS_MISSING
+    if fmt.startswith("DATETIME"):
+        value = (value - SAS_EPOCH).total_seconds()
+    elif fmt.startswith("DATE"):
+        value = (value - SAS_EPOCH.date()).days
+    elif fmt.startswith("TIME"):
+        value = value.hour * 3600 + value.minute * 60 + value.second + value.microsecond / 1e6
+    return struct.pack("<d", value)
+
+def subheader(signature, body):
+    return struct.pack("<I", signature) + body
+
+def write_sas7bdat(path, columns, rows):
+    """Writes an uncompressed, 32-bit little-endian dataset.
+
+    columns is a list of (name, width, format); width 0 marks a numeric column.
+    """
+    text = b"\0" * 8
+    refs = {}
+    for value in [c[0] for c in columns] + [c[2] for c in columns if c[2]]:
+        if value not in refs:
+            refs[value] = (len(text), len(value))
+            text += value.encode().ljust(-(-len(value) // 4) * 4, b"\0")
+    def ref(value):
+        offset, length = refs[value] if value else (0, 0)
+        return struct.pack("<HHHH", 0, offset, length, 0)
+
+    widths = [w or 8 for _, w, _ in columns]
+    offsets = [sum(widths[:i]) for i in range(len(widths))]
+    row_length = sum(widths)
+
+    def sized(signature, body):
+        # Name and attribute entries start 12 bytes in; the length after the signature excludes those
+        return subheader(signature, struct.pack("<H", len(body)) + bytes(6) + body)
+
+    row_size = bytearray(480)
+    struct.pack_into("<I", row_size, 0, 0xF7F7F7F7)
+    struct.pack_into("<II", row_size, 20, row_length, len(rows))
+    struct.pack_into("<I", row_size, 60, (SAS_PAGE_SIZE - 24) // row_length)
+    subheaders = [
+        bytes(row_size),
+        subheader(0xF6F6F6F6, struct.pack("<I", len(columns)) + bytes(4)),
+        subheader(0xFFFFFFFD, struct.pack("<H", len(text) - 8) + text[2:]),
+        sized(0xFFFFFFFF, b"".join(ref(name) for name, _, _ in columns) + bytes(8)),
+        sized(0xFFFFFFFC, b"".join(
+            struct.pack("<IIBBBB", offset, width, 0, 0, 2 if w else 1, 0)
+            for (_, w, _), offset, width in zip(columns, offsets, widths)
+        ) + bytes(8)),
+    ]
+    for _, _, fmt in columns:
+        subheaders.append(subheader(0xFFFFFBFE, bytes(30) + ref(fmt)[:6] + bytes(12)))
+
+    meta = bytearray(SAS_PAGE_SIZE)
+    struct.pack_into("<HHH", meta, 16, 0, len(subheaders), len(subheaders))
+    end = SAS_PAGE_SIZE
+    for i, data in enumerate(subheaders):
+        end -= len(data)
+        meta[end:end + len(data)] = data
+        struct.pack_into("<IIBB", meta, 24 + 12 * i, end, len(data), 0, 0)
+
+    pages = [bytes(meta)]
+    if rows:
+        data = bytearray(SAS_PAGE_SIZE)
+        struct.pack_into("<HHH", data, 16, 0x0100, len(rows), 0)
+        for i, row in enumerate(rows):
+            cells = b""
+            for (_, w, fmt), value in zip(columns, row):
+                cells += value.encode().ljust(w) if w else sas_value(fmt, value)
+            data[24 + i * row_length:24 + (i + 1) * row_length] = cells
+        pages.append(bytes(data))
+
+    header = bytearray(1024)
+    header[0:32] = SAS_MAGIC
+    header[32], header[35], header[37], header[70] = 0x22, 0x22, 0x01, 20
+    header[92:124] = b"GRIZZLY".ljust(32)
+    struct.pack_into("<III", header, 196, 1024, SAS_PAGE_SIZE, len(pages))
+    header[216:224] = b"9.0401M3"
+    with open(path, "wb") as f:
+        f.write(bytes(header) + b"".join(pages))
+
+TYPED_COLUMNS = [
+    ("name", 8, ""),
+    ("born", 0, "DATE9"),
+    ("seen", 0, "DATETIME20"),
+    ("at", 0, "TIME8"),
+    ("score", 0, ""),
+]
+TYPED_DTYPES = ["str", "date", "Timestamp(Millisecond, None)", "Time64(Microsecond)", "float64"]
+
+def test_sas_dates_and_times(data_dir):
+    path = os.path.join(data_dir, "typed.sas7bdat")
+    write_sas7bdat(path, TYPED_COLUMNS, [
+        ("alice", datetime.date(1985, 7, 14), datetime.datetime(2021, 3, 4, 5, 6, 7, 250000), datetime.time(13, 45, 30), None),
+        ("bob", datetime.date(1959, 12, 31), datetime.datetime(1960, 1, 1), datetime.time(0, 0, 1), 2.5),
+        ("carol", None, None, None, 4.0),
+    ])
+
+    df = grizzly.read_sas(path)
+    assert df.columns == ["name", "born", "seen", "at", "score"]
+    assert list(df.dtypes.values()) == TYPED_DTYPES
+    assert df["born"].to_list() == [datetime.date(1985, 7, 14), datetime.date(1959, 12, 31), None]
+    assert df["seen"].to_list() == [datetime.datetime(2021, 3, 4, 5, 6, 7, 250000), datetime.datetime(1960, 1, 1), None]
+    assert df["at"].to_list() == [datetime.time(13, 45, 30), datetime.time(0, 0, 1), None]
+
+def test_sas_datetime_rounding(data_dir):
+    # Stored as floating-point seconds, so a value just under a millisecond rounds up like in .xpt files
+    path = os.path.join(data_dir, "rounding.sas7bdat")
+    write_sas7bdat(path, [("seen", 0, "DATETIME20")], [
+        (datetime.datetime(2021, 3, 4, 5, 6, 7, 249900),),
+        (datetime.datetime(1955, 6, 1, 0, 0, 0, 999),),
+    ])
+    assert grizzly.read_sas(path)["seen"].to_list() == [
+        datetime.datetime(2021, 3, 4, 5, 6, 7, 250000),
+        datetime.datetime(1955, 6, 1, 0, 0, 0, 1000),
+    ]
+
+def test_sas_missing_first_row(data_dir):
+    # The first row used to decide the column type, so a missing first value made it a string
+    path = os.path.join(data_dir, "first_missing.sas7bdat")
+    write_sas7bdat(path, TYPED_COLUMNS, [
+        ("alice", None, None, None, None),
+        ("bob", datetime.date(2000, 2, 29), datetime.datetime(2000, 2, 29, 12), datetime.time(6, 30), 1.5),
+    ])
+
+    df = grizzly.read_sas(path, chunk_size=1)
+    assert list(df.dtypes.values()) == TYPED_DTYPES
+    assert df["born"].to_list() == [None, datetime.date(2000, 2, 29)]
+    assert df["score"].to_list() == [None, 1.5]
+
+def test_sas_empty_dataset(data_dir):
+    path = os.path.join(data_dir, "empty.sas7bdat")
+    write_sas7bdat(path, TYPED_COLUMNS, [])
+
+    df = grizzly.read_sas(path)
+    assert df.shape == (0, 5)
+    assert list(df.dtypes.values()) == TYPED_DTYPES
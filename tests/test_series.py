@@ -0,0 +1,73 @@
+import grizzly
+import pytest
+import datetime
+
+@pytest.fixture
+def df():
+    return grizzly.DataFrame({
+        "id": [3, 1, 2, None],
+        "name": ["Carol", "Alice", "Bob", "Dave"],
+        "score": [1.5, None, 3.0, 2.0],
+        "passed": [True, False, True, None],
+        "day": [datetime.date(2024, 1, 15), None, datetime.date(2023, 12, 31), datetime.date(2024, 2, 1)],
+    })
+
+def test_columns_and_dtypes(df):
+    assert df.columns == ["id", "name", "score", "passed", "day"]
+    assert df.dtypes == {"id": "int64", "name": "str", "score": "float64", "passed": "bool", "day": "date"}
+    assert grizzly.DataFrame().columns == []
+
+def test_column_access(df):
+    s = df["name"]
+    assert isinstance(s, grizzly.Series)
+    assert s.name == "name"
+    assert s.dtype == "str"
+    assert len(s) == 4
+    assert s.to_list() == ["Carol", "Alice", "Bob", "Dave"]
+    assert repr(s) == "<grizzly.Series 'name' (4 values, str)>"
+    with pytest.raises(KeyError):
+        df["missing"]
+    with pytest.raises(TypeError):
+        df[1]
+
+def test_column_subset(df):
+    sub = df[["score", "id"]]
+    assert sub.columns == ["score", "id"]
+    assert sub.rows()[0] == (1.5, 3)
+    with pytest.raises(KeyError):
+        df[["id", "missing"]]
+    with pytest.raises(ValueError):
+        df[["id", "id"]]
+
+def test_reductions(df):
+    ids = df["id"]
+    assert ids.sum() == 6
+    assert ids.mean() == 2.0
+    assert ids.min() == 1
+    assert ids.max() == 3
+    assert ids.count() == 3
+    assert ids.null_count() == 1
+
+    score = df["score"]
+    assert score.sum() == pytest.approx(6.5)
+    assert score.mean() == pytest.approx(6.5 / 3)
+
+    assert df["passed"].sum() == 2
+    assert df["name"].min() == "Alice"
+    assert df["name"].max() == "Dave"
+    assert df["day"].min() == datetime.date(2023, 12, 31)
+    with pytest.raises(TypeError, match="not numeric"):
+        df["name"].sum()
+
+def test_reductions_without_values():
+    s = grizzly.DataFrame({"a": [None, None]}, schema={"a": "int64"})["a"]
+    assert s.sum() == 0
+    assert s.mean() is None
+    assert s.min() is None
+    assert s.count() == 0
+
+def test_series_across_batches(df):
+    s = df.concat(df)["id"]
+    assert len(s) == 8
+    assert s.sum() == 12
+    assert s.to_list()[4:] == [3, 1, 2, None]
@@ -0,0 +1,144 @@
+import grizzly
+import pytest
+import os
+import struct
+import zlib
+
+SYSMIS = -1.7976931348623157e308
+F8_2 = (5 << 16) | (8 << 8) | 2
+DATE11 = (20 << 16) | (11 << 8)
+
+# Seconds between 1582-10-14 and 1970-01-01
+EPOCH = 141428 * 86400
+
+def pad(data, multiple, fill=b" "):
+    return data + fill * (-len(data) % multiple)
+
+def variable_record(name, width, fmt, label="", missing=()):
+    rec = struct.pack("<iiiiii", 2, width, 1 if label else 0, len(missing), fmt, fmt)
+    rec += pad(name.encode(), 8)
+    if label:
+        rec += struct.pack("<i", len(label)) + pad(label.encode(), 4)
+    for value in missing:
+        rec += struct.pack("<d", value)
+    return rec
+
+def encode_case(variables, case):
+    slots = []
+    for (_, width, *_), value in zip(variables, case):
+        if width == 0:
+            slots.append(("num", value))
+        else:
+            data = value.encode().ljust((width + 7) // 8 * 8)
+            slots.extend(("str", data[i:i + 8]) for i in range(0, len(data), 8))
+    return slots
+
+def bytecode(slots, bias=100.0):
+    out, commands, raw = b"", [], b""
+    for kind, value in slots:
+        if kind == "num" and value == SYSMIS:
+            commands.append(255)
+        elif kind == "num" and float(value).is_integer() and -99 <= value <= 151:
+            commands.append(int(value + bias))
+        elif kind == "str" and value == b" " * 8:
+            commands.append(254)
+        else:
+            commands.append(253)
+            raw += struct.pack("<d", value) if kind == "num" else value
+        if len(commands) == 8:
+            out += bytes(commands) + raw
+            commands, raw = [], b""
+    if commands:
+        out += bytes(commands + [0] * (8 - len(commands))) + raw
+    return out
+
+def write_sav(path, variables, cases, value_labels=(), long_names=None, zsav=False):
+    """Writes a little-endian system file, uncompressed or as a .zsav.
+
+    variables is a list of (short_name, width, format, label, missing_values).
+    """
+    header = (b"$FL3" if zsav else b"$FL2") + pad(b"grizzly tests", 60)
+    n_slots = sum(1 if v[1] == 0 else (v[1] + 7) // 8 for v in variables)
+    header += struct.pack("<iiiiid", 2, n_slots, 2 if zsav else 0, 0, len(cases), 100.0)
+    header += b"01 Jan 24" + b"00:00:00" + b" " * 64 + b"   "
+
+    body = b""
+    for name, width, fmt, label, missing in variables:
+        body += variable_record(name, width, fmt, label, missing)
+        for _ in range((width + 7) // 8 - 1):
+            body += struct.pack("<iiiiii", 2, -1, 0, 0, 0, 0) + b" " * 8
+    for labels, indexes in value_labels:
+        body += struct.pack("<ii", 3, len(labels))
+        for value, text in labels:
+            body += struct.pack("<d", value) + pad(bytes([len(text)]) + text.encode(), 8)
+        body += struct.pack("<ii", 4, len(indexes)) + b"".join(struct.pack("<i", i) for i in indexes)
+    # Machine integer info declaring UTF-8 (code page 65001)
+    body += struct.pack("<iiii", 7, 3, 4, 8) + struct.pack("<8i", 1, 0, 0, 1, 1, 1, 2, 65001)
+    if long_names:
+        text = "\t".join(f"{short}={long}" for short, long in long_names.items()).encode()
+        body += struct.pack("<iiii", 7, 13, 1, len(text)) + text
+    body += struct.pack("<ii", 999, 0)
+
+    slots = [slot for case in cases for slot in encode_case(variables, case)]
+    if zsav:
+        stream = bytecode(slots)
+        data_start = len(header) + len(body)
+        compressed = zlib.compress(stream)
+        block_ofs = data_start + 24
+        trailer_ofs = block_ofs + len(compressed)
+        trailer = struct.pack("<qqii", -100, 0, 0x3FF000, 1)
+        trailer += struct.pack("<qqii", data_start, block_ofs, len(stream), len(compressed))
+        data = struct.pack("<qqq", data_start, trailer_ofs, len(trailer)) + compressed + trailer
+    else:
+        data = b"".join(struct.pack("<d", v) if kind == "num" else v for kind, v in slots)
+    with open(path, "wb") as f:
+        f.write(header + body + data)
+
+VARIABLES = [
+    ("NAME", 10, (1 << 16) | (10 << 8), "Respondent", ()),
+    ("SCORE", 0, F8_2, "Test score", (-9.0,)),
+    ("SEX", 0, F8_2, "", ()),
+    ("BORN", 0, DATE11, "", ()),
+]
+
+CASES = [
+    ("Alice", 12.5, 1, EPOCH + 10957 * 86400),
+    ("Bob", -9.0, 2, EPOCH),
+    ("Charlie Longname", SYSMIS, 3, SYSMIS),
+]
+
+def write_sample(path, zsav=False):
+    # SEX is the third variable, but the string NAME occupies two slots
+    write_sav(path, VARIABLES, [(c[0][:10],) + c[1:] for c in CASES],
+              value_labels=[([(1.0, "male"), (2.0, "female")], [4])],
+              long_names={"SCORE": "TestScore"}, zsav=zsav)
+
+@pytest.mark.parametrize("zsav", [False, True])
+def test_read_spss(data_dir, zsav, read_lines):
+    path = os.path.join(data_dir, "survey.zsav" if zsav else "survey.sav")
+    write_sample(path, zsav)
+    df = grizzly.read_spss(path)
+    assert df.shape == (3, 4)
+
+    lines = read_lines(df)
+    assert lines[0] == "NAME,TestScore,SEX,BORN"
+    assert lines[1] == "Alice,12.5,male,2000-01-01"
+    # The user-missing value -9 and system-missing become nulls
+    assert lines[2] == "Bob,,female,1970-01-01"
+    assert lines[3] == "Charlie Lo,,3,"
+
+def test_read_spss_metadata(data_dir, read_lines):
+    path = os.path.join(data_dir, "survey.sav")
+    write_sample(path)
+    df = grizzly.read_spss(path, value_labels=False)
+    assert df.column_labels == {"NAME": "Respondent", "TestScore": "Test score", "SEX": None, "BORN": None}
+
+    lines = read_lines(df)
+    assert lines[1] == "Alice,12.5,1.0,2000-01-01"
+
+def test_read_spss_invalid(data_dir):
+    path = os.path.join(data_dir, "bad.sav")
+    with open(path, "wb") as f:
+        f.write(b"not an spss file")
+    with pytest.raises(IOError):
+        grizzly.read_spss(path)
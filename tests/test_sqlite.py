@@ -0,0 +1,91 @@
+import grizzly
+import pytest
+import os
+import sqlite3
+
+@pytest.fixture
+def sample_csv(data_dir):
+    path = os.path.join(data_dir, "test.csv")
+    with open(path, "w") as f:
+        f.write("name,age,score,joined\nAlice,30,1.5,2024-01-15\nBob,,-0.25,2023-12-31\nCharlie,35,,\n")
+    return path
+
+@pytest.fixture
+def db_path(data_dir):
+    path = os.path.join(data_dir, "test.db")
+    if os.path.exists(path):
+        os.remove(path)
+    return path
+
+def test_to_sqlite(sample_csv, db_path):
+    df = grizzly.read_csv(sample_csv)
+    df.to_sqlite(db_path, "people")
+
+    conn = sqlite3.connect(db_path)
+    rows = conn.execute("SELECT name, age, score, joined FROM people ORDER BY name").fetchall()
+    types = [row[2] for row in conn.execute("PRAGMA table_info(people)")]
+    conn.close()
+    assert rows == [
+        ("Alice", 30, 1.5, "2024-01-15"),
+        ("Bob", None, -0.25, "2023-12-31"),
+        ("Charlie", 35, None, None),
+    ]
+    assert types == ["TEXT", "INTEGER", "REAL", "DATE"]
+
+def test_sqlite_roundtrip(sample_csv, db_path, read_lines):
+    df = grizzly.read_csv(sample_csv)
+    df.to_sqlite(db_path, "people")
+
+    df2 = grizzly.read_sqlite(db_path, "SELECT * FROM people")
+    assert df2.shape == (3, 4)
+    assert read_lines(df2) == read_lines(df)
+
+def test_to_sqlite_if_exists(sample_csv, db_path):
+    df = grizzly.read_csv(sample_csv)
+    df.to_sqlite(db_path, "people")
+
+    with pytest.raises(IOError, match="already exists"):
+        df.to_sqlite(db_path, "people")
+
+    df.to_sqlite(db_path, "people", if_exists="append")
+    assert grizzly.read_sqlite(db_path, "SELECT * FROM people").shape == (6, 4)
+
+    df.to_sqlite(db_path, "people", if_exists="replace")
+    assert grizzly.read_sqlite(db_path, "SELECT * FROM people").shape == (3, 4)
+
+    with pytest.raises(IOError, match="Invalid if_exists"):
+        df.to_sqlite(db_path, "people", if_exists="merge")
+
+def test_to_sqlite_out_of_range(db_path):
+    df = grizzly.DataFrame({"id": [1, 2], "big": ["1", str(2**63)]}, schema={"big": "uint64"})
+    with pytest.raises(IOError, match="Cannot write column big"):
+        df.to_sqlite(db_path, "t")
+    # The failed write is rolled back, and values that fit are still written
+    df.head(1).to_sqlite(db_path, "t")
+    assert grizzly.read_sqlite(db_path, "SELECT * FROM t").to_dict() == {"id": [1], "big": [1]}
+
+def test_read_sqlite_storage_classes(db_path, read_lines):
+    conn = sqlite3.connect(db_path)
+    conn.execute("CREATE TABLE t (i INTEGER, mixed, flag BOOLEAN, raw BLOB, empty REAL)")
+    conn.executemany("INSERT INTO t VALUES (?, ?, ?, ?, ?)", [
+        (1, 2, 1, b"\x00\x01", None),
+        (2, 2.5, 0, None, None),
+    ])
+    conn.commit()
+    conn.close()
+
+    df = grizzly.read_sqlite(db_path, "SELECT i, mixed, flag, empty, i * 2 AS doubled FROM t")
+    assert df.shape == (2, 5)
+    lines = read_lines(df)
+    assert lines[0] == "i,mixed,flag,empty,doubled"
+    # Integers and reals in one column become Float64
+    assert lines[1] == "1,2.0,true,,2"
+    assert lines[2] == "2,2.5,false,,4"
+
+def test_read_sqlite_errors(db_path, data_dir):
+    with pytest.raises(IOError):
+        grizzly.read_sqlite(os.path.join(data_dir, "missing.db"), "SELECT 1")
+
+    sqlite3.connect(db_path).close()
+    with pytest.raises(IOError, match="no such table"):
+        grizzly.read_sqlite(db_path, "SELECT * FROM nope")
@@ -0,0 +1,173 @@
+import grizzly
+import pytest
+import os
+import struct
+
+def fixed(text, width):
+    return text.encode("utf-8").ljust(width, b"\0")
+
+# Field widths of names, formats and variable labels by release
+WIDTHS = {117: (33, 49, 81), 118: (129, 57, 321), 119: (129, 57, 321)}
+
+def strl_ref(v, o, release, order):
+    """Packs the (variable, observation) key a strL cell stores."""
+    v_len = {117: 4, 118: 2, 119: 3}[release]
+    if order == ">":
+        return v.to_bytes(v_len, "big") + o.to_bytes(8 - v_len, "big")
+    return v.to_bytes(v_len, "little") + o.to_bytes(8 - v_len, "little")
+
+def write_dta(path, variables, rows, value_labels=None, release=118, order="<", strls=()):
+    """Writes a minimal release 117, 118 or 119 file, little-endian (LSF) with
+    order "<" and big-endian (MSF) with ">".
+
+    variables is a list of (name, type_code, format, value_label_name, label);
+    rows holds the already-packed bytes of each observation and strls the
+    (v, o, text) entries of the strL table.
+    """
+    k = len(variables)
+    name_len, format_len, label_len = WIDTHS[release]
+    encoding = "latin-1" if release == 117 else "utf-8"
+    def fixed_text(text, width):
+        return text.encode(encoding).ljust(width, b"\0")
+    def pack(fmt, *values):
+        return struct.pack(order + fmt, *values)
+
+    header = (b"<header><release>" + str(release).encode() + b"</release>"
+              + b"<byteorder>" + (b"MSF" if order == ">" else b"LSF") + b"</byteorder>"
+              + b"<K>" + pack("I" if release == 119 else "H", k) + b"</K>"
+              + b"<N>" + pack("I" if release == 117 else "Q", len(rows)) + b"</N>"
+              + b"<label>" + pack("B" if release == 117 else "H", 0) + b"</label>"
+              + b"<timestamp>" + pack("B", 0) + b"</timestamp></header>")
+    gso = b""
+    for v, o, text in strls:
+        data = text.encode(encoding) + b"\0"
+        gso += b"GSO" + pack("I", v) + pack("I" if release == 117 else "Q", o) + pack("BI", 130, len(data)) + data
+    sections = [
+        b"<variable_types>" + b"".join(pack("H", v[1]) for v in variables) + b"</variable_types>",
+        b"<varnames>" + b"".join(fixed_text(v[0], name_len) for v in variables) + b"</varnames>",
+        b"<sortlist>" + b"\0\0" * (k + 1) + b"</sortlist>",
+        b"<formats>" + b"".join(fixed_text(v[2], format_len) for v in variables) + b"</formats>",
+        b"<value_label_names>" + b"".join(fixed_text(v[3], name_len) for v in variables) + b"</value_label_names>",
+        b"<variable_labels>" + b"".join(fixed_text(v[4], label_len) for v in variables) + b"</variable_labels>",
+        b"<characteristics></characteristics>",
+        b"<data>" + b"".join(rows) + b"</data>",
+        b"<strls>" + gso + b"</strls>",
+    ]
+    labels = b""
+    for name, entries in (value_labels or {}).items():
+        offsets, txt = [], b""
+        for _, text in entries:
+            offsets.append(len(txt))
+            txt += text.encode(encoding) + b"\0"
+        table = pack("ii", len(entries), len(txt))
+        table += b"".join(pack("i", o) for o in offsets)
+        table += b"".join(pack("i", code) for code, _ in entries)
+        table += txt
+        labels += b"<lbl>" + pack("i", len(table)) + fixed_text(name, name_len) + b"\0\0\0" + table + b"</lbl>"
+    sections.append(b"<value_labels>" + labels + b"</value_labels>")
+
+    start = b"<stata_dta>" + header
+    map_len = len(b"<map>") + 14 * 8 + len(b"</map>")
+    offsets = [0, len(start)]
+    pos = len(start) + map_len
+    for section in sections:
+        offsets.append(pos)
+        pos += len(section)
+    offsets.append(pos)
+    offsets.append(pos + len(b"</stata_dta>"))
+    body = b"<map>" + b"".join(pack("Q", o) for o in offsets) + b"</map>"
+    with open(path, "wb") as f:
+        f.write(start + body + b"".join(sections) + b"</stata_dta>")
+
+VARIABLES = [
+    ("name", 8, "%8s", "", "Person name"),
+    ("age", 65530, "%8.0g", "", "Age in years"),
+    ("income", 65526, "%10.0g", "", ""),
+    ("sex", 65529, "%8.0g", "sexlbl", "Sex"),
+    ("born", 65528, "%td", "", "Date of birth"),
+]
+
+def row(name, age, income, sex, born, order="<"):
+    return fixed(name, 8) + struct.pack(order + "bdhi", age, income, sex, born)
+
+MISSING_BYTE = 101
+MISSING_DOUBLE = 2.0 ** 1023
+
+@pytest.fixture
+def sample_dta(data_dir):
+    path = os.path.join(data_dir, "people.dta")
+    rows = [
+        row("Alice", 30, 1500.5, 1, 10958),
+        row("Bob", MISSING_BYTE, MISSING_DOUBLE, 2, 3653),
+        row("Charlie", 35, -2.25, 3, 0),
+    ]
+    write_dta(path, VARIABLES, rows, {"sexlbl": [(1, "male"), (2, "female")]})
+    return path
+
+def test_read_stata(sample_dta, read_lines):
+    df = grizzly.read_stata(sample_dta)
+    assert df.shape == (3, 5)
+
+    lines = read_lines(df)
+    assert lines[0] == "name,age,income,sex,born"
+    assert lines[1] == "Alice,30,1500.5,male,1990-01-01"
+    # Stata missing values become nulls
+    assert lines[2] == "Bob,,,female,1970-01-01"
+    # Codes without a label keep their number
+    assert lines[3] == "Charlie,35,-2.25,3,1960-01-01"
+
+def test_read_stata_variable_labels(sample_dta):
+    df = grizzly.read_stata(sample_dta)
+    assert df.column_labels == {
+        "name": "Person name",
+        "age": "Age in years",
+        "income": None,
+        "sex": "Sex",
+        "born": "Date of birth",
+    }
+
+def test_read_stata_without_value_labels(sample_dta, read_lines):
+    df = grizzly.read_stata(sample_dta, value_labels=False)
+    lines = read_lines(df)
+    assert lines[1] == "Alice,30,1500.5,1,1990-01-01"
+    assert lines[3] == "Charlie,35,-2.25,3,1960-01-01"
+
+def test_read_stata_invalid(data_dir):
+    path = os.path.join(data_dir, "bad.dta")
+    with open(path, "wb") as f:
+        f.write(b"not a stata file")
+    with pytest.raises(IOError):
+        grizzly.read_stata(path)
+
+def test_read_stata_117_big_endian(data_dir, read_lines):
+    path = os.path.join(data_dir, "msf117.dta")
+    rows = [
+        "José".encode("latin-1").ljust(8, b"\0") + struct.pack(">bdhi", 30, 1500.5, 1, 10958),
+        row("Bob", MISSING_BYTE, MISSING_DOUBLE, 2, -1, order=">"),
+    ]
+    write_dta(path, VARIABLES, rows, {"sexlbl": [(1, "male"), (2, "female")]}, release=117, order=">")
+
+    df = grizzly.read_stata(path)
+    assert df.column_labels["age"] == "Age in years"
+    lines = read_lines(df)
+    assert lines[0] == "name,age,income,sex,born"
+    # Release 117 text is Latin-1
+    assert lines[1] == "José,30,1500.5,male,1990-01-01"
+    assert lines[2] == "Bob,,,female,1959-12-31"
+
+@pytest.mark.parametrize("release,order", [(117, "<"), (118, "<"), (118, ">"), (119, ">")])
+def test_read_stata_strl(data_dir, release, order):
+    path = os.path.join(data_dir, "strl.dta")
+    variables = [("id", 65529, "%8.0g", "", ""), ("note", 32768, "%9s", "", "Free text")]
+    rows = [
+        struct.pack(order + "h", 1) + strl_ref(2, 1, release, order),
+        # (0, 0) is an empty strL
+        struct.pack(order + "h", 2) + strl_ref(0, 0, release, order),
+        struct.pack(order + "h", 3) + strl_ref(2, 3, release, order),
+    ]
+    strls = [(2, 1, "a long note, " * 300), (2, 3, "naïve café")]
+    write_dta(path, variables, rows, release=release, order=order, strls=strls)
+
+    df = grizzly.read_stata(path)
+    assert df["id"].to_list() == [1, 2, 3]
+    assert df["note"].to_list() == ["a long note, " * 300, "", strls[1][2]]
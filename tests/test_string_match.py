@@ -0,0 +1,63 @@
+import grizzly
+import pytest
+from grizzly import col
+
+@pytest.fixture
+def df():
+    return grizzly.DataFrame({
+        "name": ["Alice", "bob", "Charlie", None, "alicia", "100%_sure"],
+        "prefix": ["Al", "b", "X", "Y", "al", "1"],
+    })
+
+def names(df):
+    return df.to_dict()["name"]
+
+def test_ordering_comparisons(df):
+    assert names(df.query("name < 'C'")) == ["Alice", "100%_sure"]
+    assert names(df.query("name >= 'b' and name <= 'bz'")) == ["bob"]
+    assert names(df.query("name between 'A' and 'Z'")) == ["Alice", "Charlie"]
+    assert names(df.filter(col("name") > "a")) == ["bob", "alicia"]
+
+def test_like(df):
+    assert names(df.query("name like 'Al%'")) == ["Alice"]
+    assert names(df.query("name ilike 'al%'")) == ["Alice", "alicia"]
+    assert names(df.query("name like '_ob'")) == ["bob"]
+    assert names(df.query("name like '100\\%\\_%'")) == ["100%_sure"]
+    assert names(df.query("name not like '%i%'")) == ["bob", "100%_sure"]
+    assert names(df.query("name NOT ILIKE '%LI%'")) == ["bob", "100%_sure"]
+
+def test_startswith_endswith_contains(df):
+    assert names(df.query("name startswith 'Al'")) == ["Alice"]
+    assert names(df.query("name endswith 'e'")) == ["Alice", "Charlie", "100%_sure"]
+    assert names(df.query("name contains 'li'")) == ["Alice", "Charlie", "alicia"]
+    assert names(df.query("name not contains 'li'")) == ["bob", "100%_sure"]
+    # The pattern can be another column
+    assert names(df.query("name startswith prefix")) == ["Alice", "bob", "alicia", "100%_sure"]
+
+def test_regex(df):
+    assert names(df.query("name ~ '^[A-Z]'")) == ["Alice", "Charlie"]
+    assert names(df.query("name ~* '^al'")) == ["Alice", "alicia"]
+    assert names(df.query("name !~ 'li'")) == ["bob", "100%_sure"]
+    assert names(df.query("name !~* 'LI'")) == ["bob", "100%_sure"]
+    with pytest.raises(ValueError):
+        df.query("name ~ '('")
+
+def test_expr_methods(df):
+    assert names(df.filter(col("name").like("%ice"))) == ["Alice"]
+    assert names(df.filter(col("name").ilike("ALI%"))) == ["Alice", "alicia"]
+    assert names(df.filter(col("name").starts_with("b") | col("name").ends_with("ia"))) == ["bob", "alicia"]
+    assert names(df.filter(col("name").contains("%"))) == ["100%_sure"]
+    assert names(df.filter(col("name").matches(r"^\w+$"))) == ["Alice", "bob", "Charlie", "alicia"]
+    assert names(df.filter(col("name").matches("BOB", case_insensitive=True))) == ["bob"]
+    assert names(df.filter(col("name").matches(col("prefix")))) == ["Alice", "bob", "alicia", "100%_sure"]
+    out = df.with_columns(has_li=col("name").contains("li"))
+    assert out.to_dict()["has_li"] == [True, False, True, None, True, False]
+    assert repr(col("name").like("A%")) == "col(\"name\").like('A%')"
+    assert repr(col("name").matches("a", case_insensitive=True)) == "col(\"name\").matches('a', case_insensitive=True)"
+
+def test_non_string_operands():
+    df = grizzly.DataFrame({"n": [1, 2]})
+    with pytest.raises(TypeError, match="like needs string operands"):
+        df.query("n like '1%'")
+    with pytest.raises(TypeError, match="string operands"):
+        df.filter(col("n").matches("1"))
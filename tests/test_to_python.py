@@ -0,0 +1,104 @@
+import grizzly
+import pytest
+import datetime
+import decimal
+import json
+
+def sample():
+    return grizzly.DataFrame({"id": [1, 2, 3], "name": ["Alice", "Bob", None], "score": [1.5, None, 3.0]})
+
+def test_to_dict_list():
+    assert sample().to_dict() == {"id": [1, 2, 3], "name": ["Alice", "Bob", None], "score": [1.5, None, 3.0]}
+
+def test_to_dict_records():
+    records = [
+        {"id": 1, "name": "Alice", "score": 1.5},
+        {"id": 2, "name": "Bob", "score": None},
+        {"id": 3, "name": None, "score": 3.0},
+    ]
+    df = sample()
+    assert df.to_dict(orient="records") == records
+    assert df.to_pylist() == records
+    with pytest.raises(ValueError, match="Unknown orient"):
+        df.to_dict(orient="index")
+
+def test_rows():
+    assert sample().rows() == [(1, "Alice", 1.5), (2, "Bob", None), (3, None, 3.0)]
+
+def test_roundtrip_records():
+    records = sample().to_pylist()
+    assert grizzly.from_records(records).to_pylist() == records
+
+def test_multiple_batches():
+    df = sample().concat(sample())
+    assert df.to_dict()["id"] == [1, 2, 3, 1, 2, 3]
+    assert len(df.rows()) == 6
+
+def test_empty():
+    assert grizzly.DataFrame().to_dict() == {}
+    assert grizzly.DataFrame().rows() == []
+
+def test_temporal_values():
+    tz = datetime.timezone(datetime.timedelta(hours=2))
+    values = {
+        "day": [datetime.date(2024, 1, 15), None],
+        "at": [datetime.datetime(2024, 1, 15, 8, 30, 0, 250000), None],
+        "time": [datetime.time(13, 45, 5, 10), None],
+        "flag": [True, None],
+        "raw": [b"\x00\x01", None],
+    }
+    assert grizzly.DataFrame(values).to_dict() == values
+
+    aware = grizzly.DataFrame({"at": [datetime.datetime(2024, 1, 15, 10, 30, tzinfo=tz)]}).rows()[0][0]
+    assert aware == datetime.datetime(2024, 1, 15, 8, 30, tzinfo=datetime.timezone.utc)
+    assert aware.utcoffset() == datetime.timedelta(0)
+
+def zigzag(n):
+    n = (n << 1) ^ (n >> 63)
+    out = b""
+    while True:
+        byte = n & 0x7F
+        n >>= 7
+        if n:
+            out += bytes([byte | 0x80])
+        else:
+            return out + bytes([byte])
+
+def test_decimals_and_millisecond_timestamps():
+    schema = {"type": "record", "name": "r", "fields": [
+        {"name": "amount", "type": ["null", {"type": "bytes", "logicalType": "decimal", "precision": 9, "scale": 2}]},
+        {"name": "at", "type": {"type": "long", "logicalType": "timestamp-millis"}},
+    ]}
+    meta = {"avro.schema": json.dumps(schema).encode(), "avro.codec": b"null"}
+    header = b"Obj\x01" + zigzag(len(meta))
+    for key, value in meta.items():
+        header += zigzag(len(key)) + key.encode() + zigzag(len(value)) + value
+    sync = b"0123456789abcdef"
+    # 1234.56 is the unscaled integer 123456 = 0x01E240
+    block = zigzag(1) + zigzag(3) + b"\x01\xe2\x40" + zigzag(1704067200123) + zigzag(0) + zigzag(0)
+    data = header + zigzag(0) + sync + zigzag(2) + zigzag(len(block)) + block + sync
+
+    rows = grizzly.read_avro(data).rows()
+    assert rows[0] == (decimal.Decimal("1234.56"),
+                       datetime.datetime(2024, 1, 1, 0, 0, 0, 123000, tzinfo=datetime.timezone.utc))
+    assert isinstance(rows[0][0], decimal.Decimal)
+    assert rows[1] == (None, datetime.datetime(1970, 1, 1, tzinfo=datetime.timezone.utc))
+
+def test_to_numpy_rejects_strings():
+    with pytest.raises(ValueError, match="column name is"):
+        sample().to_numpy()
+
+def test_to_numpy():
+    np = pytest.importorskip("numpy")
+    ints = grizzly.DataFrame({"a": [1, 2], "b": [3, 4]}).to_numpy()
+    assert ints.dtype == np.int64
+    assert ints.tolist() == [[1, 3], [2, 4]]
+
+    floats = grizzly.DataFrame({"a": [1, None], "b": [0.5, 1.5]}).to_numpy()
+    assert floats.dtype == np.float64
+    assert floats[0].tolist() == [1.0, 0.5]
+    assert np.isnan(floats[1, 0])
+
+    bools = grizzly.DataFrame({"a": [True, False]}).to_numpy()
+    assert bools.dtype == np.bool_
+    assert bools.tolist() == [[True], [False]]
@@ -0,0 +1,91 @@
+import grizzly
+import pytest
+import os
+
+@pytest.fixture
+def sample_csv(data_dir):
+    path = os.path.join(data_dir, "test.csv")
+    with open(path, "w") as f:
+        f.write("name,age,score\nAlice,30,1.5\nBob,25,-0.001\nCharlie,35,123456.789\n")
+    return path
+
+def test_xpt_roundtrip_v5(sample_csv, data_dir):
+    df = grizzly.read_csv(sample_csv)
+    path = os.path.join(data_dir, "test.xpt")
+    df.to_xpt(path)
+
+    # Version 5 files are a whole number of 80-byte records
+    assert os.path.getsize(path) % 80 == 0
+
+    df2 = grizzly.read_xpt(path)
+    assert df2.shape == (3, 3)
+
+    csv_path = os.path.join(data_dir, "roundtrip.csv")
+    df2.to_csv(csv_path)
+    with open(csv_path) as f:
+        lines = f.read().splitlines()
+    assert lines[0] == "name,age,score"
+    assert lines[1] == "Alice,30.0,1.5"
+    assert lines[2] == "Bob,25.0,-0.001"
+    assert lines[3] == "Charlie,35.0,123456.789"
+
+def test_xpt_roundtrip_v8_long_names(data_dir):
+    path = os.path.join(data_dir, "long.csv")
+    with open(path, "w") as f:
+        f.write("a_rather_long_column_name,city\n1,New York\n2,\n")
+    df = grizzly.read_csv(path)
+
+    xpt_path = os.path.join(data_dir, "long.xpt")
+    with pytest.raises(OSError):
+        df.to_xpt(xpt_path, version=5)
+
+    df.to_xpt(xpt_path, name="cities", version=8)
+    df2 = grizzly.read_xpt(xpt_path)
+    assert df2.shape == (2, 2)
+    assert list(df2.column_labels.keys()) == ["a_rather_long_column_name", "city"]
+
+def test_xpt_invalid_file(sample_csv):
+    with pytest.raises(OSError):
+        grizzly.read_xpt(sample_csv)
+
+# The first namestr starts at record 8 and stores its position at bytes 84-88;
+# the member header stores the namestr length at bytes 74-78 of record 3
+@pytest.mark.parametrize("offset,patch", [(8 * 80 + 84, (1000000).to_bytes(4, "big")), (3 * 80 + 74, b"0040")])
+def test_xpt_corrupt_file(sample_csv, data_dir, offset, patch):
+    path = os.path.join(data_dir, "corrupt.xpt")
+    grizzly.read_csv(sample_csv).to_xpt(path)
+    with open(path, "r+b") as f:
+        f.seek(offset)
+        f.write(patch)
+    with pytest.raises(OSError, match="Failed to read XPORT file"):
+        grizzly.read_xpt(path)
+
+def test_xpt_dates(data_dir):
+    path = os.path.join(data_dir, "dates.csv")
+    with open(path, "w") as f:
+        f.write("visit,value\n2020-01-31,-118.625\n1960-01-01,1\n")
+    df = grizzly.read_csv(path)
+    xpt_path = os.path.join(data_dir, "dates.xpt")
+    df.to_xpt(xpt_path)
+
+    with open(xpt_path, "rb") as f:
+        raw = f.read()
+    # Dates are SAS day counts (21945 days after 1960-01-01) as IBM floats
+    assert bytes.fromhex("4455b90000000000c276a00000000000") in raw
+
+    csv_path = os.path.join(data_dir, "dates_out.csv")
+    grizzly.read_xpt(xpt_path).to_csv(csv_path)
+    with open(csv_path) as f:
+        assert f.read().splitlines()[1:] == ["2020-01-31,-118.625", "1960-01-01,1.0"]
+
+def test_xpt_datetimes_and_times(data_dir):
+    import datetime
+    values = [datetime.datetime(2021, 3, 4, 5, 6, 7, 249000), datetime.datetime(1955, 6, 1, 0, 0, 0, 1000), None]
+    times = [datetime.time(13, 45, 30, 500000), datetime.time(0, 0, 0, 1000), None]
+    path = os.path.join(data_dir, "times.xpt")
+    grizzly.DataFrame({"at": values, "t": times}).to_xpt(path)
+
+    df = grizzly.read_xpt(path)
+    # Decoded like SAS7BDAT files: rounded to the nearest millisecond, not truncated
+    assert df["at"].to_list() == values
+    assert df["t"].to_list() == times
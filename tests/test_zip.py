@@ -0,0 +1,69 @@
+import grizzly
+import pytest
+import io
+import os
+import zipfile
+
+ORDERS = "order_id,amount\n1,9.5\n2,12.25\n3,100.0\n"
+CUSTOMERS = "customer_id,name\n10,Alice\n11,Bob\n"
+
+@pytest.fixture
+def bundle(data_dir):
+    path = os.path.join(data_dir, "bundle.zip")
+    with zipfile.ZipFile(path, "w", compression=zipfile.ZIP_DEFLATED) as zf:
+        zf.writestr("orders.csv", ORDERS)
+        zf.writestr("nested/", "")
+        zf.writestr("nested/customers.csv", CUSTOMERS)
+        zf.writestr("stored.csv", ORDERS, compress_type=zipfile.ZIP_STORED)
+    return path
+
+def csv_text(df):
+    buf = io.BytesIO()
+    df.to_csv(buf)
+    return buf.getvalue().decode()
+
+def test_zip_members(bundle):
+    assert grizzly.zip_members(bundle) == ["orders.csv", "nested/customers.csv", "stored.csv"]
+    with open(bundle, "rb") as f:
+        assert grizzly.zip_members(f.read()) == ["orders.csv", "nested/customers.csv", "stored.csv"]
+
+def test_read_csv_member(bundle):
+    df = grizzly.read_csv(bundle, member="orders.csv")
+    assert csv_text(df) == ORDERS
+    df = grizzly.read_csv(bundle, member="nested/customers.csv")
+    assert csv_text(df) == CUSTOMERS
+    df = grizzly.read_csv(bundle, member="stored.csv")
+    assert csv_text(df) == ORDERS
+
+def test_read_csv_member_from_bytes(bundle):
+    with open(bundle, "rb") as f:
+        df = grizzly.read_csv(f.read(), member="orders.csv")
+    assert df.shape == (3, 2)
+
+def test_read_bang_path(bundle):
+    assert csv_text(grizzly.read_csv(bundle + "!orders.csv")) == ORDERS
+    assert csv_text(grizzly.read_csv(bundle + "!nested/customers.csv")) == CUSTOMERS
+
+def test_bang_path_other_readers(bundle, data_dir):
+    df = grizzly.read_csv(bundle + "!orders.csv")
+    buf = io.BytesIO()
+    df.to_parquet(buf)
+    path = os.path.join(data_dir, "parquet.zip")
+    with zipfile.ZipFile(path, "w") as zf:
+        zf.writestr("orders.parquet", buf.getvalue())
+    assert csv_text(grizzly.read_parquet(path + "!orders.parquet")) == ORDERS
+
+def test_missing_member(bundle):
+    with pytest.raises(IOError, match="No member named missing.csv"):
+        grizzly.read_csv(bundle, member="missing.csv")
+    with pytest.raises(IOError, match="No member named missing.csv"):
+        grizzly.read_csv(bundle + "!missing.csv")
+
+def test_not_a_zip(data_dir):
+    path = os.path.join(data_dir, "plain.csv")
+    with open(path, "w") as f:
+        f.write(ORDERS)
+    with pytest.raises(IOError, match="zip archive"):
+        grizzly.read_csv(path, member="orders.csv")
+    with pytest.raises(IOError, match="zip archive"):
+        grizzly.zip_members(path)